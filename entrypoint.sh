@@ -5,6 +5,48 @@ set -e
 : "${self_port:=8080}"
 : "${to_ip:=localhost}"
 : "${to_port:=50051}"
+: "${stream_mode:=shared}"
+: "${viewer_queue_capacity:=64}"
+: "${outbound_queue_capacity:=256}"
+: "${coalesce_motion:=false}"
+: "${stop_on_disconnect:=true}"
+: "${pong_timeout_secs:=45}"
+: "${heartbeat_timeout_ms:=0}"
+: "${exclusive_lease:=false}"
+: "${lease_ttl_secs:=30}"
+: "${command_ack_timeout_ms:=3000}"
+: "${max_command_age_ms:=0}"
+: "${estop_reset_roles:=admin}"
+: "${allow_unauthenticated_estop_reset:=false}"
+: "${vendor_catalog_path:=}"
+: "${max_speed:=2.0}"
+: "${max_angular_speed:=2.0}"
+: "${max_move_duration_ms:=10000}"
+: "${speed_policy:=reject}"
+: "${metrics_log_interval_secs:=60}"
+: "${auth_enabled:=false}"
+: "${jwt_algorithm:=HS256}"
+: "${jwt_key_file:=}"
+: "${jwt_issuer:=}"
+: "${jwt_audience:=}"
+: "${policy_path:=}"
+: "${api_key_header:=x-api-key}"
+: "${api_keys_file:=}"
+: "${oidc_jwks_path:=}"
+: "${oidc_issuer:=}"
+: "${oidc_audience:=}"
+: "${oidc_reload_check_secs:=10}"
+: "${oidc_role_claims:=roles}"
+: "${oidc_role_map:=}"
+
+# "admin,safety" -> "admin", "safety" (TOML 배열 원소)
+estop_reset_roles_toml=$(printf '%s' "${estop_reset_roles}" | tr ',' '\n' | sed 's/^ *//; s/ *$//; /^$/d; s/.*/"&"/' | paste -sd, - | sed 's/,/, /g')
+
+# "realm_access.roles,groups" -> "realm_access.roles", "groups"
+oidc_role_claims_toml=$(printf '%s' "${oidc_role_claims}" | tr ',' '\n' | sed 's/^ *//; s/ *$//; /^$/d; s/.*/"&"/' | paste -sd, - | sed 's/,/, /g')
+
+# "robot-admins=admin,robot-operators=operator" -> "robot-admins" = "admin" (TOML table 항목)
+oidc_role_map_toml=$(printf '%s' "${oidc_role_map}" | tr ',' '\n' | sed -n 's/^ *\([^=]*[^= ]\) *= *\(.*[^ ]\) *$/"\1" = "\2"/p')
 
 mkdir -p /app/config
 
@@ -12,10 +54,55 @@ cat > /app/config/default.toml <<EOF
 [websocket_server]
 self_ip = "${self_ip}"
 self_port = "${self_port}"
+viewer_queue_capacity = ${viewer_queue_capacity}
 
 [grpc_client]
 to_ip = "${to_ip}"
 to_port = "${to_port}"
+stream_mode = "${stream_mode}"
+outbound_queue_capacity = ${outbound_queue_capacity}
+coalesce_motion = ${coalesce_motion}
+
+[control]
+stop_on_disconnect = ${stop_on_disconnect}
+pong_timeout_secs = ${pong_timeout_secs}
+heartbeat_timeout_ms = ${heartbeat_timeout_ms}
+exclusive_lease = ${exclusive_lease}
+lease_ttl_secs = ${lease_ttl_secs}
+command_ack_timeout_ms = ${command_ack_timeout_ms}
+max_command_age_ms = ${max_command_age_ms}
+estop_reset_roles = [${estop_reset_roles_toml}]
+allow_unauthenticated_estop_reset = ${allow_unauthenticated_estop_reset}
+vendor_catalog_path = "${vendor_catalog_path}"
+
+[control.limits]
+max_speed = ${max_speed}
+max_angular_speed = ${max_angular_speed}
+max_move_duration_ms = ${max_move_duration_ms}
+speed_policy = "${speed_policy}"
+
+[metrics]
+log_interval_secs = ${metrics_log_interval_secs}
+
+[auth]
+enabled = ${auth_enabled}
+jwt_algorithm = "${jwt_algorithm}"
+jwt_key_file = "${jwt_key_file}"
+issuer = "${jwt_issuer}"
+audience = "${jwt_audience}"
+policy_path = "${policy_path}"
+api_key_header = "${api_key_header}"
+api_keys_file = "${api_keys_file}"
+
+[auth.oidc]
+jwks_path = "${oidc_jwks_path}"
+issuer = "${oidc_issuer}"
+audience = "${oidc_audience}"
+reload_check_secs = ${oidc_reload_check_secs}
+role_claims = [${oidc_role_claims_toml}]
+
+[auth.oidc.role_map]
+${oidc_role_map_toml}
 EOF
 
 exec /usr/local/bin/realtime-control-gateway
@@ -1,46 +1,219 @@
 use crate::protocol::websocket::WebSocketHandler;
 use crate::protocol::grpc::GrpcClient;
+use crate::app::metrics::GatewayMetrics;
+use crate::auth::Authenticator;
+use crate::domain::catalog::CommandCatalog;
+use crate::config::configs::{ControlConfig, Settings};
 use std::sync::Arc;
-use tokio::sync::RwLock;
+use tokio::sync::{watch, RwLock};
 use tokio::net::TcpListener;
+use tokio::task::JoinSet;
+use tokio::time::{self, Duration};
 use crate::session::manager::{SessionManager, SharedSessions};
-use log::{info, warn, error, debug};
+use log::{info, warn};
+
+// 종료 시 control 세션들이 dead-man STOP을 보내고 정리될 때까지 기다리는 최대 시간
+const SHUTDOWN_GRACE: Duration = Duration::from_secs(5);
+// 그 뒤 큐에 남은 STOP 등이 gRPC 스트림으로 나갈 때까지 기다리는 최대 시간
+const SHUTDOWN_FLUSH: Duration = Duration::from_secs(2);
 
 pub struct GatewayApp {
     grpc: Arc<GrpcClient>,
     sessions: SharedSessions,
+    control: Arc<ControlConfig>,
+    metrics: Arc<GatewayMetrics>,
+    catalog: Arc<CommandCatalog>,
+    auth: Arc<Authenticator>,
+    metrics_interval: Duration,
 }
 
 impl GatewayApp {
-    pub async fn new(grpc_endpoint: String) -> anyhow::Result<Self> {
-        let grpc_client = GrpcClient::connect(grpc_endpoint).await?;
+    pub async fn new(grpc_endpoint: String, settings: Settings) -> anyhow::Result<Self> {
+        // vendor 명령 카탈로그 (경로가 비어 있으면 vendor 명령은 모두 unknown_command)
+        let catalog = if settings.control.vendor_catalog_path.is_empty() {
+            CommandCatalog::default()
+        } else {
+            let catalog = CommandCatalog::load(&settings.control.vendor_catalog_path)?;
+            info!(
+                "vendor command catalog loaded: {} command(s) from {}",
+                catalog.command_count(),
+                settings.control.vendor_catalog_path
+            );
+            catalog
+        };
+
+        let auth = Authenticator::from_config(&settings.auth)?;
+        if auth.is_enabled() {
+            info!(
+                "websocket auth enabled (jwt={}, oidc_keys={}, api_keys={})",
+                if auth.has_jwt() { format!("{:?}", settings.auth.jwt_algorithm) } else { "off".to_string() },
+                auth.oidc_key_count().map_or("off".to_string(), |n| n.to_string()),
+                auth.api_key_count()
+            );
+        } else {
+            warn!("websocket auth disabled: every client can connect");
+        }
+        if auth.policy().is_enabled() {
+            info!(
+                "access policy loaded: {} role(s) from {}",
+                auth.policy().role_count(),
+                settings.auth.policy_path
+            );
+        }
+
+        let metrics = Arc::new(GatewayMetrics::default());
+
+        let grpc_client = GrpcClient::connect(grpc_endpoint, &settings.grpc_client, metrics.clone()).await?;
         let grpc = Arc::new(grpc_client);
 
-        let sessions: SharedSessions = Arc::new(RwLock::new(SessionManager::new()));
+        let control = settings.control;
+        if !auth.is_enabled() {
+            if control.allow_unauthenticated_estop_reset {
+                warn!("websocket auth disabled and allow_unauthenticated_estop_reset set: every control client can send reset_estop");
+            } else {
+                warn!("websocket auth disabled: reset_estop is denied (set control.allow_unauthenticated_estop_reset to allow it)");
+            }
+        } else if control.estop_reset_roles.is_empty() {
+            warn!("control.estop_reset_roles is empty: an emergency stop latch can only be cleared by restarting");
+        } else {
+            for id in auth.api_keys_without_role() {
+                warn!("api key {id:?} has no role: it cannot send reset_estop");
+            }
+        }
+        let lease_ttl = Duration::from_secs(control.lease_ttl_secs);
+        let sessions: SharedSessions = Arc::new(RwLock::new(SessionManager::new(
+            lease_ttl,
+            settings.websocket_server.viewer_queue_capacity,
+            control.rate_limit.robot,
+        )));
+
+        Ok(Self {
+            grpc,
+            sessions,
+            control: Arc::new(control),
+            metrics,
+            catalog: Arc::new(catalog),
+            auth: Arc::new(auth),
+            metrics_interval: Duration::from_secs(settings.metrics.log_interval_secs),
+        })
+    }
+
+    /// 큐 깊이와 드롭 카운터를 로그로 남긴다. 비어 있는 큐는 생략한다.
+    async fn log_metrics(&self) {
+        let snapshot = self.metrics.snapshot();
+        info!(
+            "[metrics] motion_dropped={} motion_coalesced={} outbound_rejected={} rate_limited={} slow_viewers_disconnected={}",
+            snapshot.motion_dropped,
+            snapshot.motion_coalesced,
+            snapshot.outbound_rejected,
+            snapshot.rate_limited,
+            snapshot.slow_viewers_disconnected
+        );
 
-        Ok(Self { grpc, sessions })
+        for (key, depth) in self.grpc.queue_depths().await {
+            if depth.priority + depth.control + depth.signaling > 0 {
+                info!(
+                    "[metrics] grpc outbound queue key={key:?} priority={} control={} signaling={}",
+                    depth.priority, depth.control, depth.signaling
+                );
+            }
+        }
+        for (robot_id, session_id, depth) in self.sessions.read().await.viewer_queue_depths() {
+            info!("[metrics] viewer queue robot_id={robot_id} session_id={session_id} depth={depth}");
+        }
     }
 
     pub async fn run(&self, bind_addr: &str) -> anyhow::Result<()> {
         let listener = TcpListener::bind(bind_addr).await?;
         info!("Gateway listening start : {}", bind_addr);
 
+        let (shutdown_tx, shutdown_rx) = watch::channel(false);
+        let mut connections = JoinSet::new();
+        let shutdown = shutdown_signal();
+        tokio::pin!(shutdown);
+
+        // log_interval_secs = 0이면 metrics 로그를 끈다.
+        let metrics_enabled = !self.metrics_interval.is_zero();
+        let mut metrics_tick = time::interval(self.metrics_interval.max(Duration::from_secs(1)));
+        metrics_tick.tick().await;
+
         loop {
-            let (stream, _) = listener.accept().await?;
+            tokio::select! {
+                _ = metrics_tick.tick(), if metrics_enabled => {
+                    self.log_metrics().await;
+                }
+                accepted = listener.accept() => {
+                    let (stream, _) = accepted?;
 
-            info!("receive request! from: {:?}", stream.peer_addr());
+                    info!("receive request! from: {:?}", stream.peer_addr());
 
-            let grpc = self.grpc.clone();
-            let sessions = self.sessions.clone();
+                    let grpc = self.grpc.clone();
+                    let sessions = self.sessions.clone();
+                    let control = self.control.clone();
+                    let metrics = self.metrics.clone();
+                    let catalog = self.catalog.clone();
+                    let auth = self.auth.clone();
+                    let shutdown = shutdown_rx.clone();
 
-            tokio::spawn(async move {
-                let handler = WebSocketHandler::new(grpc, sessions);
-                info!("make websocket handler");
+                    connections.spawn(async move {
+                        let handler = WebSocketHandler::new(grpc, sessions, control, metrics, catalog, auth, shutdown);
+                        info!("make websocket handler");
 
-                if let Err(e) = handler.handle_connection(stream).await {
-                    info!("WebSocket error: {:?}", e);
+                        if let Err(e) = handler.handle_connection(stream).await {
+                            info!("WebSocket error: {:?}", e);
+                        }
+                    });
+                }
+                // 끝난 연결 task 회수
+                Some(_) = connections.join_next(), if !connections.is_empty() => {}
+                _ = &mut shutdown => {
+                    info!("shutdown signal received, closing {} connection(s)", connections.len());
+                    break;
                 }
-            });
+            }
+        }
+
+        // 각 세션에 종료를 알리고 (control 세션은 dead-man STOP 전송) 정리될 때까지 기다린다.
+        let _ = shutdown_tx.send(true);
+        let drained = time::timeout(SHUTDOWN_GRACE, async {
+            while connections.join_next().await.is_some() {}
+        })
+        .await;
+        if drained.is_err() {
+            warn!("shutdown grace period elapsed, aborting {} connection(s)", connections.len());
         }
+
+        // dead-man STOP은 outbound 큐에 들어갔을 뿐이므로, runtime을 내리기 전에 실제로 나가기를 기다린다.
+        if time::timeout(SHUTDOWN_FLUSH, self.grpc.flush_outbound()).await.is_err() {
+            warn!("gRPC outbound queues were not flushed within {SHUTDOWN_FLUSH:?}");
+        } else {
+            info!("gRPC outbound queues flushed");
+        }
+
+        Ok(())
+    }
+}
+
+/// Ctrl-C 또는 (unix) SIGTERM을 기다린다. docker stop은 SIGTERM을 보낸다.
+async fn shutdown_signal() {
+    #[cfg(unix)]
+    {
+        let mut term = match tokio::signal::unix::signal(tokio::signal::unix::SignalKind::terminate()) {
+            Ok(sig) => sig,
+            Err(e) => {
+                warn!("failed to install SIGTERM handler: {e}");
+                let _ = tokio::signal::ctrl_c().await;
+                return;
+            }
+        };
+        tokio::select! {
+            _ = tokio::signal::ctrl_c() => {}
+            _ = term.recv() => {}
+        }
+    }
+
+    #[cfg(not(unix))]
+    {
+        let _ = tokio::signal::ctrl_c().await;
     }
 }
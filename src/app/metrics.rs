@@ -0,0 +1,57 @@
+use std::sync::atomic::{AtomicU64, Ordering};
+
+/// 운영 중 확인용 카운터. 주기적으로 `[metrics]` 로그로 출력된다.
+#[derive(Debug, Default)]
+pub struct GatewayMetrics {
+    // 나가지 못하고 버려진 control 명령 수 (drop-oldest, STOP/E-STOP에 밀린 명령)
+    motion_dropped: AtomicU64,
+    // 아직 나가지 않은 move/set_speed/velocity를 새 값으로 덮어쓴 수 (coalesce_motion)
+    motion_coalesced: AtomicU64,
+    // outbound 큐가 가득 차서 거절된 명령/signaling 수
+    outbound_rejected: AtomicU64,
+    // [control.rate_limit] 한도 초과로 거절된 명령 수
+    rate_limited: AtomicU64,
+    // viewer 큐가 가득 차서 끊은 느린 viewer 수
+    slow_viewers_disconnected: AtomicU64,
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct MetricsSnapshot {
+    pub motion_dropped: u64,
+    pub motion_coalesced: u64,
+    pub outbound_rejected: u64,
+    pub rate_limited: u64,
+    pub slow_viewers_disconnected: u64,
+}
+
+impl GatewayMetrics {
+    pub fn record_motion_dropped(&self) {
+        self.motion_dropped.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub fn record_motion_coalesced(&self) {
+        self.motion_coalesced.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub fn record_outbound_rejected(&self) {
+        self.outbound_rejected.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub fn record_rate_limited(&self) {
+        self.rate_limited.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub fn record_slow_viewer_disconnected(&self) {
+        self.slow_viewers_disconnected.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub fn snapshot(&self) -> MetricsSnapshot {
+        MetricsSnapshot {
+            motion_dropped: self.motion_dropped.load(Ordering::Relaxed),
+            motion_coalesced: self.motion_coalesced.load(Ordering::Relaxed),
+            outbound_rejected: self.outbound_rejected.load(Ordering::Relaxed),
+            rate_limited: self.rate_limited.load(Ordering::Relaxed),
+            slow_viewers_disconnected: self.slow_viewers_disconnected.load(Ordering::Relaxed),
+        }
+    }
+}
@@ -1 +1,2 @@
-pub mod gateway_app;
\ No newline at end of file
+pub mod gateway_app;
+pub mod metrics;
@@ -0,0 +1,214 @@
+use std::collections::HashMap;
+use std::fs;
+use std::sync::Arc;
+
+use anyhow::{anyhow, Context};
+use sha2::{Digest, Sha256};
+
+use crate::auth::policy::Grant;
+use crate::auth::{AuthError, Identity};
+use crate::config::configs::ApiKeyConfig;
+
+struct ApiKey {
+    id: String,
+    role: Option<String>,
+    scope: Arc<Grant>,
+}
+
+/// 정적 API key 목록. config에는 key의 sha256만 있고, 요청의 key를 해시해서 찾는다.
+pub struct ApiKeyStore {
+    keys: HashMap<[u8; 32], ApiKey>,
+}
+
+impl ApiKeyStore {
+    /// config의 api_keys와 api_keys_file(있으면)의 key를 합쳐서 읽는다.
+    pub fn load(configs: &[ApiKeyConfig], path: &str) -> anyhow::Result<Self> {
+        if path.is_empty() {
+            return Self::from_config(configs);
+        }
+        let raw = fs::read_to_string(path).with_context(|| format!("failed to read api keys file {path}"))?;
+        let from_file: Vec<ApiKeyConfig> =
+            serde_json::from_str(&raw).with_context(|| format!("failed to parse api keys file {path}"))?;
+        Self::from_config(&[configs, from_file.as_slice()].concat())
+    }
+
+    pub fn from_config(configs: &[ApiKeyConfig]) -> anyhow::Result<Self> {
+        let mut keys = HashMap::new();
+        for config in configs {
+            let mut hash = [0u8; 32];
+            hex::decode_to_slice(config.sha256.trim(), &mut hash)
+                .with_context(|| format!("api key {:?}: sha256 must be 64 hex characters", config.id))?;
+            let scope = Grant::new(config.robots.clone(), config.screen, config.commands.clone())
+                .with_context(|| format!("api key {:?}: invalid scope", config.id))?;
+
+            let key = ApiKey {
+                id: config.id.clone(),
+                role: config.role.clone(),
+                scope: Arc::new(scope),
+            };
+            if let Some(existing) = keys.insert(hash, key) {
+                return Err(anyhow!("api key {:?} has the same hash as {:?}", config.id, existing.id));
+            }
+        }
+        Ok(Self { keys })
+    }
+
+    pub fn len(&self) -> usize {
+        self.keys.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.keys.is_empty()
+    }
+
+    /// role이 없는 key의 id (reset_estop을 보낼 수 없다)
+    pub fn ids_without_role(&self) -> Vec<&str> {
+        let mut ids: Vec<&str> = self
+            .keys
+            .values()
+            .filter(|key| key.role.is_none())
+            .map(|key| key.id.as_str())
+            .collect();
+        ids.sort_unstable();
+        ids
+    }
+
+    pub fn verify(&self, key: &str) -> Result<Identity, AuthError> {
+        let hash: [u8; 32] = Sha256::digest(key.as_bytes()).into();
+        let key = self.keys.get(&hash).ok_or(AuthError::InvalidApiKey)?;
+        Ok(Identity {
+            subject: key.id.clone(),
+            role: key.role.clone(),
+            method: "api_key",
+            key_id: Some(key.id.clone()),
+            scope: Some(key.scope.clone()),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::{json, Value};
+
+    use super::*;
+    use crate::auth::policy::AccessPolicy;
+    use crate::domain::control::ControlRequestType;
+
+    fn sha256_hex(key: &str) -> String {
+        hex::encode(Sha256::digest(key.as_bytes()))
+    }
+
+    fn config(value: Value) -> ApiKeyConfig {
+        serde_json::from_value(value).unwrap()
+    }
+
+    fn key(id: &str, secret: &str) -> ApiKeyConfig {
+        config(json!({ "id": id, "sha256": sha256_hex(secret) }))
+    }
+
+    #[test]
+    fn verify_matches_the_sha256_of_the_key() {
+        let mut operator = key("ci-bot", "s3cret");
+        operator.role = Some("operator".to_string());
+        let store = ApiKeyStore::from_config(&[operator, key("no-role", "other")]).unwrap();
+        assert_eq!(store.len(), 2);
+
+        let identity = store.verify("s3cret").unwrap();
+        assert_eq!(identity.subject, "ci-bot");
+        assert_eq!(identity.role.as_deref(), Some("operator"));
+        assert_eq!(identity.method, "api_key");
+        assert_eq!(identity.key_id.as_deref(), Some("ci-bot"));
+
+        assert!(matches!(store.verify("S3CRET"), Err(AuthError::InvalidApiKey)));
+        assert!(matches!(store.verify(""), Err(AuthError::InvalidApiKey)));
+        // 해시 문자열 자체를 key로 보내도 통하지 않는다.
+        assert!(matches!(store.verify(&sha256_hex("s3cret")), Err(AuthError::InvalidApiKey)));
+        assert_eq!(store.ids_without_role(), vec!["no-role"]);
+    }
+
+    #[test]
+    fn sha256_is_trimmed_and_case_insensitive() {
+        let config = config(json!({ "id": "upper", "sha256": format!(" {} ", sha256_hex("k").to_uppercase()) }));
+        let store = ApiKeyStore::from_config(&[config]).unwrap();
+        assert_eq!(store.verify("k").unwrap().subject, "upper");
+    }
+
+    #[test]
+    fn bad_or_wrong_length_hex_is_rejected() {
+        let valid = sha256_hex("k");
+        for sha256 in [
+            "not-hex".to_string(),
+            format!("{}zz", &valid[..62]),
+            valid[..62].to_string(),
+            format!("{valid}00"),
+            String::new(),
+        ] {
+            let err = ApiKeyStore::from_config(&[config(json!({ "id": "bad", "sha256": sha256 }))])
+                .err()
+                .unwrap();
+            assert!(err.to_string().contains("sha256 must be 64 hex characters"), "{sha256:?}: {err}");
+        }
+    }
+
+    #[test]
+    fn duplicate_hashes_are_rejected() {
+        let err = ApiKeyStore::from_config(&[key("first", "same"), key("second", "same")])
+            .err()
+            .unwrap();
+        assert!(err.to_string().contains("same hash"), "{err}");
+    }
+
+    #[test]
+    fn unknown_command_in_scope_is_rejected() {
+        let config = config(json!({ "id": "bad-scope", "sha256": sha256_hex("k"), "commands": ["fly"] }));
+        let err = ApiKeyStore::from_config(&[config]).err().unwrap();
+        assert!(err.to_string().contains("invalid scope"), "{err}");
+    }
+
+    #[test]
+    fn load_merges_api_keys_file_with_inline_keys() {
+        let path = std::env::temp_dir().join(format!("gateway-api-keys-{}.json", std::process::id()));
+        fs::write(&path, json!([{ "id": "from-file", "sha256": sha256_hex("file-key") }]).to_string()).unwrap();
+
+        let store = ApiKeyStore::load(&[key("inline", "inline-key")], path.to_str().unwrap()).unwrap();
+        assert_eq!(store.len(), 2);
+        assert_eq!(store.verify("inline-key").unwrap().subject, "inline");
+        assert_eq!(store.verify("file-key").unwrap().subject, "from-file");
+
+        // 파일과 config에 같은 key가 있으면 기동 시점에 실패한다.
+        let err = ApiKeyStore::load(&[key("inline", "file-key")], path.to_str().unwrap())
+            .err()
+            .unwrap();
+        assert!(err.to_string().contains("same hash"), "{err}");
+
+        let store = ApiKeyStore::load(&[key("inline", "inline-key")], "").unwrap();
+        assert_eq!(store.len(), 1);
+        assert!(ApiKeyStore::load(&[], "/nonexistent/api-keys.json").is_err());
+    }
+
+    #[test]
+    fn key_scope_limits_robots_and_commands_through_policy() {
+        let config = config(json!({
+            "id": "dock-bot",
+            "sha256": sha256_hex("k"),
+            "robots": ["lab-*"],
+            "commands": ["dock", "undock"]
+        }));
+        let identity = ApiKeyStore::from_config(&[config]).unwrap().verify("k").unwrap();
+        // 정책 파일이 없어도(전부 허용) key의 범위가 적용된다.
+        let policy = AccessPolicy::default();
+
+        assert!(policy.can_control(&identity, "lab-01"));
+        assert!(!policy.can_control(&identity, "robot-01"));
+        assert!(!policy.can_view(&identity, "lab-01"));
+        assert!(policy.can_send(&identity, "lab-01", ControlRequestType::Dock));
+        assert!(!policy.can_send(&identity, "lab-01", ControlRequestType::Move));
+        assert!(!policy.can_send(&identity, "robot-01", ControlRequestType::Dock));
+        // e_stop은 범위와 관계없이 허용된다.
+        assert!(policy.can_send(&identity, "robot-01", ControlRequestType::EStop));
+
+        // 기본값: 모든 robot, 명령 없음
+        let identity = ApiKeyStore::from_config(&[key("viewer", "v")]).unwrap().verify("v").unwrap();
+        assert!(!policy.can_control(&identity, "lab-01"));
+    }
+}
@@ -0,0 +1,193 @@
+use std::fs;
+
+use anyhow::Context;
+use jsonwebtoken::{decode, Algorithm, DecodingKey, Validation};
+use serde_json::{Map, Value};
+
+use crate::auth::{AuthError, Identity};
+use crate::config::configs::{AuthConfig, JwtAlgorithm};
+
+/// HS256/RS256 JWT 검증. 키는 로컬 파일에서 한 번 읽는다.
+pub struct JwtVerifier {
+    key: DecodingKey,
+    validation: Validation,
+    role_claim: String,
+}
+
+impl JwtVerifier {
+    pub fn from_config(config: &AuthConfig) -> anyhow::Result<Self> {
+        let raw = fs::read(&config.jwt_key_file)
+            .with_context(|| format!("failed to read jwt key file {:?}", config.jwt_key_file))?;
+        Self::from_key(config, &raw)
+    }
+
+    /// 이미 읽은 키 파일 내용으로 만든다. (HS256: secret, RS256: 공개키 PEM)
+    pub fn from_key(config: &AuthConfig, raw: &[u8]) -> anyhow::Result<Self> {
+        let (algorithm, key) = match config.jwt_algorithm {
+            // shared secret: 파일 끝의 개행은 키에 포함하지 않는다.
+            JwtAlgorithm::Hs256 => (Algorithm::HS256, DecodingKey::from_secret(raw.trim_ascii_end())),
+            JwtAlgorithm::Rs256 => (
+                Algorithm::RS256,
+                DecodingKey::from_rsa_pem(raw).context("invalid RS256 public key (expected PEM)")?,
+            ),
+        };
+
+        let mut validation = Validation::new(algorithm);
+        if !config.issuer.is_empty() {
+            validation.set_issuer(&[config.issuer.as_str()]);
+        }
+        if config.audience.is_empty() {
+            validation.validate_aud = false;
+        } else {
+            validation.set_audience(&[config.audience.as_str()]);
+        }
+
+        Ok(Self {
+            key,
+            validation,
+            role_claim: config.role_claim.clone(),
+        })
+    }
+
+    pub fn verify(&self, token: &str) -> Result<Identity, AuthError> {
+        let data = decode::<Map<String, Value>>(token, &self.key, &self.validation)
+            .map_err(|e| AuthError::InvalidToken(e.to_string()))?;
+        let claims = data.claims;
+
+        let subject = claims
+            .get("sub")
+            .and_then(Value::as_str)
+            .ok_or_else(|| AuthError::InvalidToken("sub claim is missing".to_string()))?
+            .to_string();
+        // role claim은 문자열 또는 문자열 배열(첫 번째 값)을 받는다.
+        let role = match claims.get(&self.role_claim) {
+            Some(Value::String(role)) => Some(role.clone()),
+            Some(Value::Array(roles)) => roles.iter().find_map(Value::as_str).map(str::to_string),
+            _ => None,
+        };
+
+        Ok(Identity {
+            subject,
+            role,
+            method: "jwt",
+            key_id: None,
+            scope: None,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::{SystemTime, UNIX_EPOCH};
+
+    use jsonwebtoken::{encode, EncodingKey, Header};
+    use serde_json::json;
+
+    const SECRET: &[u8] = b"test-secret";
+    const RSA_PRIVATE: &str = include_str!("testdata/rsa_private.pem");
+    const RSA_PUBLIC: &str = include_str!("testdata/rsa_public.pem");
+
+    fn config(algorithm: JwtAlgorithm) -> AuthConfig {
+        AuthConfig {
+            enabled: true,
+            jwt_algorithm: algorithm,
+            issuer: "https://issuer.test".to_string(),
+            audience: "gateway".to_string(),
+            ..AuthConfig::default()
+        }
+    }
+
+    fn hs256() -> JwtVerifier {
+        JwtVerifier::from_key(&config(JwtAlgorithm::Hs256), SECRET).unwrap()
+    }
+
+    fn rs256() -> JwtVerifier {
+        JwtVerifier::from_key(&config(JwtAlgorithm::Rs256), RSA_PUBLIC.as_bytes()).unwrap()
+    }
+
+    fn now() -> u64 {
+        SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_secs()
+    }
+
+    fn claims() -> Value {
+        json!({
+            "sub": "alice",
+            "role": "operator",
+            "iss": "https://issuer.test",
+            "aud": "gateway",
+            "exp": now() + 600,
+        })
+    }
+
+    fn sign_hs256(claims: &Value) -> String {
+        encode(&Header::new(Algorithm::HS256), claims, &EncodingKey::from_secret(SECRET)).unwrap()
+    }
+
+    fn sign_rs256(claims: &Value) -> String {
+        let key = EncodingKey::from_rsa_pem(RSA_PRIVATE.as_bytes()).unwrap();
+        encode(&Header::new(Algorithm::RS256), claims, &key).unwrap()
+    }
+
+    fn rejected(verifier: &JwtVerifier, token: &str) -> String {
+        match verifier.verify(token) {
+            Err(AuthError::InvalidToken(reason)) => reason,
+            other => panic!("expected InvalidToken, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn valid_tokens_yield_subject_and_role() {
+        let identity = hs256().verify(&sign_hs256(&claims())).unwrap();
+        assert_eq!(identity.subject, "alice");
+        assert_eq!(identity.role.as_deref(), Some("operator"));
+        assert_eq!(identity.method, "jwt");
+
+        // role claim이 배열이면 첫 번째 문자열을 쓴다.
+        let mut with_roles = claims();
+        with_roles["role"] = json!(["admin", "operator"]);
+        let identity = rs256().verify(&sign_rs256(&with_roles)).unwrap();
+        assert_eq!(identity.role.as_deref(), Some("admin"));
+    }
+
+    #[test]
+    fn expired_token_is_rejected() {
+        let mut expired = claims();
+        expired["exp"] = json!(now() - 3600);
+        assert!(rejected(&hs256(), &sign_hs256(&expired)).contains("ExpiredSignature"));
+        assert!(rejected(&rs256(), &sign_rs256(&expired)).contains("ExpiredSignature"));
+    }
+
+    #[test]
+    fn token_signed_with_another_algorithm_is_rejected() {
+        rejected(&hs256(), &sign_rs256(&claims()));
+        rejected(&rs256(), &sign_hs256(&claims()));
+
+        // 공개키를 HS256 secret으로 쓴 토큰 (algorithm confusion)
+        let forged = encode(
+            &Header::new(Algorithm::HS256),
+            &claims(),
+            &EncodingKey::from_secret(RSA_PUBLIC.as_bytes()),
+        )
+        .unwrap();
+        rejected(&rs256(), &forged);
+    }
+
+    #[test]
+    fn wrong_issuer_or_audience_is_rejected() {
+        let mut wrong_iss = claims();
+        wrong_iss["iss"] = json!("https://other.test");
+        assert!(rejected(&hs256(), &sign_hs256(&wrong_iss)).contains("InvalidIssuer"));
+
+        let mut wrong_aud = claims();
+        wrong_aud["aud"] = json!("other-service");
+        assert!(rejected(&rs256(), &sign_rs256(&wrong_aud)).contains("InvalidAudience"));
+    }
+
+    #[test]
+    fn token_without_sub_is_rejected() {
+        let mut anonymous = claims();
+        anonymous.as_object_mut().unwrap().remove("sub");
+        assert!(rejected(&hs256(), &sign_hs256(&anonymous)).contains("sub claim"));
+    }
+}
@@ -0,0 +1,262 @@
+pub mod api_key;
+pub mod jwt;
+pub mod oidc;
+pub mod policy;
+pub mod token;
+
+use std::fmt;
+use std::sync::Arc;
+
+use tokio_tungstenite::tungstenite::handshake::server::Request;
+
+use crate::auth::api_key::ApiKeyStore;
+use crate::auth::jwt::JwtVerifier;
+use crate::auth::oidc::OidcVerifier;
+use crate::auth::policy::{AccessPolicy, Grant};
+use crate::auth::token::{extract_api_key, extract_token, TokenSource};
+use crate::config::configs::AuthConfig;
+
+/// handshake에서 확인된 클라이언트 신원. screen/control 핸들러로 전달된다.
+#[derive(Debug, Clone)]
+pub struct Identity {
+    /// 사용자 id (JWT sub) 또는 API key id
+    pub subject: String,
+    /// 권한 판단에 쓰는 role (없을 수 있음)
+    pub role: Option<String>,
+    /// 인증 방식 (로그용)
+    pub method: &'static str,
+    /// API key로 인증했으면 key id
+    pub key_id: Option<String>,
+    /// API key에 붙은 robot/명령 범위. 있으면 권한 정책 파일 대신 이 범위가 적용된다.
+    pub scope: Option<Arc<Grant>>,
+}
+
+impl Identity {
+    /// 인증을 끈 경우의 신원
+    pub fn anonymous() -> Self {
+        Self {
+            subject: "anonymous".to_string(),
+            role: None,
+            method: "none",
+            key_id: None,
+            scope: None,
+        }
+    }
+}
+
+impl fmt::Display for Identity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} (role={}, via {})", self.subject, self.role.as_deref().unwrap_or("-"), self.method)
+    }
+}
+
+/// 접근 감사 기록 (log target "audit")
+pub fn audit(identity: &Identity, robot_id: &str, action: impl fmt::Display, outcome: &str) {
+    log::info!(
+        target: "audit",
+        "subject={} method={} key_id={} role={} robot_id={robot_id} action={action} outcome={outcome}",
+        identity.subject,
+        identity.method,
+        identity.key_id.as_deref().unwrap_or("-"),
+        identity.role.as_deref().unwrap_or("-"),
+    );
+}
+
+#[derive(Debug)]
+pub enum AuthError {
+    /// 토큰이 없음
+    MissingToken,
+    /// 등록되지 않은 API key
+    InvalidApiKey,
+    /// 서명/만료/issuer/audience 검증 실패
+    InvalidToken(String),
+}
+
+impl fmt::Display for AuthError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AuthError::MissingToken => f.write_str("missing access token"),
+            AuthError::InvalidApiKey => f.write_str("invalid api key"),
+            AuthError::InvalidToken(reason) => write!(f, "invalid access token: {reason}"),
+        }
+    }
+}
+
+impl std::error::Error for AuthError {}
+
+/// 인증 성공 결과
+pub struct Authenticated {
+    pub identity: Identity,
+    /// 토큰을 Sec-WebSocket-Protocol로 받았으면 응답에 돌려줘야 하는 subprotocol
+    pub protocol: Option<&'static str>,
+}
+
+/// WebSocket handshake 인증. 키는 기동 시 로컬 파일에서 읽어둔다.
+pub struct Authenticator {
+    enabled: bool,
+    query_param: String,
+    api_key_header: String,
+    jwt: Option<JwtVerifier>,
+    oidc: Option<OidcVerifier>,
+    api_keys: ApiKeyStore,
+    policy: AccessPolicy,
+}
+
+impl Authenticator {
+    pub fn from_config(config: &AuthConfig) -> anyhow::Result<Self> {
+        // API key만 쓰는 배포도 있으므로 JWT 키 파일은 선택이다.
+        let jwt = if config.enabled && !config.jwt_key_file.is_empty() {
+            Some(JwtVerifier::from_config(config)?)
+        } else {
+            None
+        };
+        let oidc = if config.enabled && !config.oidc.jwks_path.is_empty() {
+            Some(OidcVerifier::from_config(&config.oidc)?)
+        } else {
+            None
+        };
+        let api_keys = ApiKeyStore::load(&config.api_keys, &config.api_keys_file)?;
+        if config.enabled && jwt.is_none() && oidc.is_none() && api_keys.is_empty() {
+            anyhow::bail!("auth is enabled but none of jwt_key_file, oidc.jwks_path or api_keys is configured");
+        }
+
+        let policy = if config.policy_path.is_empty() {
+            AccessPolicy::default()
+        } else {
+            AccessPolicy::load(&config.policy_path)?
+        };
+
+        Ok(Self {
+            enabled: config.enabled,
+            query_param: config.query_param.clone(),
+            api_key_header: config.api_key_header.clone(),
+            jwt,
+            oidc,
+            api_keys,
+            policy,
+        })
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.enabled
+    }
+
+    /// handshake 이후 채널/명령 권한 판단에 쓰는 정책
+    pub fn policy(&self) -> &AccessPolicy {
+        &self.policy
+    }
+
+    pub fn has_jwt(&self) -> bool {
+        self.jwt.is_some()
+    }
+
+    /// OIDC JWKS에서 읽은 키 개수 (OIDC를 쓰지 않으면 None)
+    pub fn oidc_key_count(&self) -> Option<usize> {
+        self.oidc.as_ref().map(OidcVerifier::key_count)
+    }
+
+    pub fn api_key_count(&self) -> usize {
+        self.api_keys.len()
+    }
+
+    /// role이 없는 API key의 id
+    pub fn api_keys_without_role(&self) -> Vec<&str> {
+        self.api_keys.ids_without_role()
+    }
+
+    /// handshake 요청을 검증한다. API key header가 있으면 API key로,
+    /// 없으면 JWT로 인증한다. (header -> subprotocol -> query param 순서)
+    pub fn authenticate(&self, req: &Request) -> Result<Authenticated, AuthError> {
+        if !self.enabled {
+            return Ok(Authenticated {
+                identity: Identity::anonymous(),
+                protocol: None,
+            });
+        }
+
+        if let Some(key) = extract_api_key(req, &self.api_key_header) {
+            return Ok(Authenticated {
+                identity: self.api_keys.verify(&key)?,
+                protocol: None,
+            });
+        }
+
+        let (token, source) = extract_token(req, &self.query_param).ok_or(AuthError::MissingToken)?;
+        // 둘 다 설정돼 있으면 kid header가 있는 토큰은 OIDC, 없는 토큰은 정적 키 JWT로 본다.
+        let identity = match (&self.oidc, &self.jwt) {
+            (Some(oidc), Some(jwt)) => {
+                if has_kid(&token) {
+                    oidc.verify(&token)?
+                } else {
+                    jwt.verify(&token)?
+                }
+            }
+            (Some(oidc), None) => oidc.verify(&token)?,
+            (None, Some(jwt)) => jwt.verify(&token)?,
+            (None, None) => return Err(AuthError::MissingToken),
+        };
+
+        Ok(Authenticated {
+            identity,
+            protocol: (source == TokenSource::Subprotocol).then_some(token::BEARER_PROTOCOL),
+        })
+    }
+}
+
+fn has_kid(token: &str) -> bool {
+    jsonwebtoken::decode_header(token).is_ok_and(|header| header.kid.is_some())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::configs::JwtAlgorithm;
+    use jsonwebtoken::{encode, EncodingKey, Header};
+    use serde_json::json;
+
+    const SECRET: &[u8] = b"test-secret";
+
+    fn authenticator() -> Authenticator {
+        let config = AuthConfig {
+            enabled: true,
+            jwt_algorithm: JwtAlgorithm::Hs256,
+            ..AuthConfig::default()
+        };
+        Authenticator {
+            enabled: true,
+            query_param: config.query_param.clone(),
+            api_key_header: config.api_key_header.clone(),
+            jwt: Some(JwtVerifier::from_key(&config, SECRET).unwrap()),
+            oidc: None,
+            api_keys: ApiKeyStore::from_config(&[]).unwrap(),
+            policy: AccessPolicy::default(),
+        }
+    }
+
+    fn token() -> String {
+        let claims = json!({ "sub": "alice", "exp": 4_102_444_800u64 });
+        encode(&Header::default(), &claims, &EncodingKey::from_secret(SECRET)).unwrap()
+    }
+
+    fn request(header: &str, value: &str) -> Request {
+        Request::builder().uri("/control").header(header, value).body(()).unwrap()
+    }
+
+    #[test]
+    fn bearer_subprotocol_is_echoed_back() {
+        let req = request("Sec-WebSocket-Protocol", &format!("bearer, {}", token()));
+        let authenticated = authenticator().authenticate(&req).unwrap();
+        assert_eq!(authenticated.identity.subject, "alice");
+        assert_eq!(authenticated.protocol, Some(token::BEARER_PROTOCOL));
+
+        // Authorization header로 받으면 돌려줄 subprotocol이 없다.
+        let req = request("Authorization", &format!("Bearer {}", token()));
+        assert_eq!(authenticator().authenticate(&req).unwrap().protocol, None);
+    }
+
+    #[test]
+    fn missing_token_is_rejected() {
+        let req = Request::builder().uri("/control").body(()).unwrap();
+        assert!(matches!(authenticator().authenticate(&req), Err(AuthError::MissingToken)));
+    }
+}
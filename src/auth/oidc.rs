@@ -0,0 +1,416 @@
+use std::collections::HashMap;
+use std::fs;
+use std::str::FromStr;
+use std::sync::Mutex;
+use std::time::SystemTime;
+
+use anyhow::{anyhow, Context};
+use jsonwebtoken::jwk::JwkSet;
+use jsonwebtoken::{decode, decode_header, Algorithm, DecodingKey, Validation};
+use serde_json::{Map, Value};
+use tokio::time::{Duration, Instant};
+
+use crate::auth::{AuthError, Identity};
+use crate::config::configs::OidcConfig;
+
+/// JWKS 파일에서 읽은 서명 키 (kid -> key)
+struct JwksState {
+    keys: HashMap<String, (DecodingKey, Algorithm)>,
+    modified: Option<SystemTime>,
+    checked: Instant,
+    // 모르는 kid 때문에 강제로 확인한 마지막 시각
+    forced: Option<Instant>,
+}
+
+/// OIDC ID/access token 검증. 서명 키는 로컬 JWKS 파일에서 읽고,
+/// 파일의 mtime이 바뀌면 재시작 없이 다시 읽는다. (SSO 키 rotation 대응)
+pub struct OidcVerifier {
+    jwks_path: String,
+    issuer: String,
+    audience: String,
+    reload_check: Duration,
+    role_claims: Vec<String>,
+    role_map: HashMap<String, String>,
+    state: Mutex<JwksState>,
+}
+
+impl OidcVerifier {
+    pub fn from_config(config: &OidcConfig) -> anyhow::Result<Self> {
+        if config.issuer.is_empty() || config.audience.is_empty() {
+            return Err(anyhow!("auth.oidc requires both issuer and audience"));
+        }
+        let modified = fs::metadata(&config.jwks_path).and_then(|m| m.modified()).ok();
+        let keys = load_jwks(&config.jwks_path)?;
+        if keys.is_empty() {
+            return Err(anyhow!("jwks file {:?} has no usable signing key", config.jwks_path));
+        }
+
+        Ok(Self {
+            jwks_path: config.jwks_path.clone(),
+            issuer: config.issuer.clone(),
+            audience: config.audience.clone(),
+            reload_check: Duration::from_secs(config.reload_check_secs),
+            role_claims: config.role_claims.clone(),
+            role_map: config.role_map.clone(),
+            state: Mutex::new(JwksState {
+                keys,
+                modified,
+                checked: Instant::now(),
+                forced: None,
+            }),
+        })
+    }
+
+    pub fn key_count(&self) -> usize {
+        self.state.lock().unwrap().keys.len()
+    }
+
+    /// JWKS 파일이 바뀌었으면 다시 읽는다. 평소에는 reload_check 간격마다 한 번 확인하고,
+    /// `force`(모르는 kid)는 간격을 기다리지 않지만 그 자체도 reload_check 간격에 한 번으로 제한한다.
+    /// (모르는 kid 토큰이 몰려도 요청마다 파일을 확인하지 않는다) 새 파일이 잘못됐으면 기존 키를 그대로 쓴다.
+    fn refresh(&self, force: bool) {
+        let mut state = self.state.lock().unwrap();
+        let now = Instant::now();
+        let due = |last: Instant| now.duration_since(last) >= self.reload_check;
+        if force {
+            if state.forced.is_some_and(|last| !due(last)) {
+                return;
+            }
+            state.forced = Some(now);
+        } else if !due(state.checked) {
+            return;
+        }
+        state.checked = now;
+
+        let modified = match fs::metadata(&self.jwks_path).and_then(|m| m.modified()) {
+            Ok(modified) => Some(modified),
+            Err(e) => {
+                log::warn!("[auth] failed to stat jwks file {}: {e}", self.jwks_path);
+                return;
+            }
+        };
+        if modified == state.modified {
+            return;
+        }
+
+        match load_jwks(&self.jwks_path) {
+            Ok(keys) if !keys.is_empty() => {
+                log::info!("[auth] jwks reloaded from {}: {} key(s)", self.jwks_path, keys.len());
+                state.keys = keys;
+                state.modified = modified;
+            }
+            Ok(_) => log::warn!("[auth] jwks file {} has no usable signing key, keeping previous keys", self.jwks_path),
+            Err(e) => log::warn!("[auth] failed to reload jwks, keeping previous keys: {e:#}"),
+        }
+    }
+
+    /// kid로 키를 찾는다. 모르는 kid면 rotation일 수 있으므로 파일을 바로 다시 확인한다. (reload_check 간격에 한 번)
+    fn key_for(&self, kid: &str) -> Option<(DecodingKey, Algorithm)> {
+        self.refresh(false);
+        if let Some(key) = self.state.lock().unwrap().keys.get(kid) {
+            return Some(key.clone());
+        }
+        self.refresh(true);
+        self.state.lock().unwrap().keys.get(kid).cloned()
+    }
+
+    pub fn verify(&self, token: &str) -> Result<Identity, AuthError> {
+        let header = decode_header(token).map_err(|e| AuthError::InvalidToken(e.to_string()))?;
+        let kid = header
+            .kid
+            .ok_or_else(|| AuthError::InvalidToken("kid header is missing".to_string()))?;
+        let (key, algorithm) = self
+            .key_for(&kid)
+            .ok_or_else(|| AuthError::InvalidToken(format!("unknown signing key {kid:?}")))?;
+        if header.alg != algorithm {
+            return Err(AuthError::InvalidToken(format!(
+                "token alg {:?} does not match key {kid:?} ({algorithm:?})",
+                header.alg
+            )));
+        }
+
+        let mut validation = Validation::new(algorithm);
+        validation.set_issuer(&[self.issuer.as_str()]);
+        validation.set_audience(&[self.audience.as_str()]);
+        validation.set_required_spec_claims(&["exp", "iss", "aud", "sub"]);
+
+        let claims = decode::<Map<String, Value>>(token, &key, &validation)
+            .map_err(|e| AuthError::InvalidToken(e.to_string()))?
+            .claims;
+        let subject = claims
+            .get("sub")
+            .and_then(Value::as_str)
+            .ok_or_else(|| AuthError::InvalidToken("sub claim is missing".to_string()))?
+            .to_string();
+
+        Ok(Identity {
+            subject,
+            role: self.map_role(&claims),
+            method: "oidc",
+            key_id: None,
+            scope: None,
+        })
+    }
+
+    /// role_claims를 순서대로 보고, role_map에 있는 첫 값을 gateway role로 쓴다.
+    /// role_map이 비어 있으면 claim 값을 그대로 role로 쓴다.
+    fn map_role(&self, claims: &Map<String, Value>) -> Option<String> {
+        self.role_claims
+            .iter()
+            .filter_map(|path| claim_at(claims, path))
+            .flat_map(claim_strings)
+            .find_map(|value| {
+                if self.role_map.is_empty() {
+                    Some(value.to_string())
+                } else {
+                    self.role_map.get(value).cloned()
+                }
+            })
+    }
+}
+
+/// `realm_access.roles`처럼 '.'으로 중첩 claim을 가리킨다.
+fn claim_at<'a>(claims: &'a Map<String, Value>, path: &str) -> Option<&'a Value> {
+    let mut parts = path.split('.');
+    let mut value = claims.get(parts.next()?)?;
+    for part in parts {
+        value = value.get(part)?;
+    }
+    Some(value)
+}
+
+/// claim 값이 문자열이면 그 하나, 배열이면 문자열 원소들
+fn claim_strings(value: &Value) -> Vec<&str> {
+    match value {
+        Value::String(s) => vec![s.as_str()],
+        Value::Array(items) => items.iter().filter_map(Value::as_str).collect(),
+        _ => Vec::new(),
+    }
+}
+
+/// kid와 alg가 있는 서명 키만 쓴다. (alg가 없으면 알고리즘 혼동을 막을 수 없다)
+fn load_jwks(path: &str) -> anyhow::Result<HashMap<String, (DecodingKey, Algorithm)>> {
+    let raw = fs::read_to_string(path).with_context(|| format!("failed to read jwks file {path}"))?;
+    let set: JwkSet = serde_json::from_str(&raw).with_context(|| format!("failed to parse jwks file {path}"))?;
+
+    let mut keys = HashMap::new();
+    for jwk in &set.keys {
+        let (Some(kid), Some(alg)) = (&jwk.common.key_id, &jwk.common.key_algorithm) else {
+            log::warn!("[auth] skipping jwk without kid/alg in {path}");
+            continue;
+        };
+        let algorithm = match Algorithm::from_str(&alg.to_string()) {
+            Ok(Algorithm::HS256 | Algorithm::HS384 | Algorithm::HS512) | Err(_) => {
+                log::warn!("[auth] skipping jwk {kid:?} with unsupported alg {alg} in {path}");
+                continue;
+            }
+            Ok(algorithm) => algorithm,
+        };
+        let key = DecodingKey::from_jwk(jwk).with_context(|| format!("invalid jwk {kid:?} in {path}"))?;
+        keys.insert(kid.clone(), (key, algorithm));
+    }
+    Ok(keys)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs::File;
+    use std::path::{Path, PathBuf};
+    use std::time::UNIX_EPOCH;
+
+    use jsonwebtoken::{encode, EncodingKey, Header};
+    use serde_json::json;
+
+    const RSA_PRIVATE: &str = include_str!("testdata/rsa_private.pem");
+    const JWKS: &str = include_str!("testdata/jwks.json");
+
+    /// testdata의 RSA 키를 kid/alg만 바꿔서 쓴다.
+    fn rsa_jwk(kid: &str, alg: &str) -> Value {
+        let mut jwk = serde_json::from_str::<Value>(JWKS).unwrap()["keys"][0].clone();
+        jwk["kid"] = json!(kid);
+        jwk["alg"] = json!(alg);
+        jwk
+    }
+
+    fn hmac_jwk(kid: &str) -> Value {
+        json!({ "kty": "oct", "kid": kid, "alg": "HS256", "k": "c2VjcmV0" })
+    }
+
+    /// 테스트마다 다른 JWKS 파일 (mtime을 직접 지정해 reload를 확인한다)
+    fn write_jwks(name: &str, keys: &[Value], mtime_secs: u64) -> PathBuf {
+        let path = std::env::temp_dir().join(format!("gateway-oidc-{}-{name}.json", std::process::id()));
+        fs::write(&path, json!({ "keys": keys }).to_string()).unwrap();
+        set_mtime(&path, mtime_secs);
+        path
+    }
+
+    fn set_mtime(path: &Path, secs: u64) {
+        File::options()
+            .write(true)
+            .open(path)
+            .unwrap()
+            .set_modified(UNIX_EPOCH + std::time::Duration::from_secs(secs))
+            .unwrap();
+    }
+
+    fn config(path: &Path) -> OidcConfig {
+        OidcConfig {
+            jwks_path: path.to_string_lossy().into_owned(),
+            issuer: "https://sso.test/realms/robots".to_string(),
+            audience: "gateway".to_string(),
+            reload_check_secs: 0,
+            ..OidcConfig::default()
+        }
+    }
+
+    fn claims() -> Value {
+        let now = SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_secs();
+        json!({
+            "sub": "alice",
+            "iss": "https://sso.test/realms/robots",
+            "aud": "gateway",
+            "exp": now + 600,
+            "roles": ["operator"],
+        })
+    }
+
+    fn sign(alg: Algorithm, kid: &str, claims: &Value) -> String {
+        let mut header = Header::new(alg);
+        header.kid = Some(kid.to_string());
+        let key = match alg {
+            Algorithm::HS256 => EncodingKey::from_secret(b"secret"),
+            _ => EncodingKey::from_rsa_pem(RSA_PRIVATE.as_bytes()).unwrap(),
+        };
+        encode(&header, claims, &key).unwrap()
+    }
+
+    fn rejected(verifier: &OidcVerifier, token: &str) -> String {
+        match verifier.verify(token) {
+            Err(AuthError::InvalidToken(reason)) => reason,
+            other => panic!("expected InvalidToken, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn token_is_verified_with_the_key_of_its_kid() {
+        let path = write_jwks("kid", &[rsa_jwk("rsa-1", "RS256")], 1_000);
+        let verifier = OidcVerifier::from_config(&config(&path)).unwrap();
+
+        let identity = verifier.verify(&sign(Algorithm::RS256, "rsa-1", &claims())).unwrap();
+        assert_eq!(identity.subject, "alice");
+        assert_eq!(identity.role.as_deref(), Some("operator"));
+        assert_eq!(identity.method, "oidc");
+
+        assert!(rejected(&verifier, &sign(Algorithm::RS256, "rsa-2", &claims())).contains("unknown signing key"));
+        fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn header_alg_must_match_the_jwk_alg() {
+        let path = write_jwks("alg", &[rsa_jwk("rsa-384", "RS384")], 1_000);
+        let verifier = OidcVerifier::from_config(&config(&path)).unwrap();
+
+        let reason = rejected(&verifier, &sign(Algorithm::RS256, "rsa-384", &claims()));
+        assert!(reason.contains("does not match"), "{reason}");
+        assert!(verifier.verify(&sign(Algorithm::RS384, "rsa-384", &claims())).is_ok());
+        fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn hmac_jwks_are_skipped() {
+        let path = write_jwks("hmac", &[hmac_jwk("hmac-1"), rsa_jwk("rsa-1", "RS256")], 1_000);
+        let verifier = OidcVerifier::from_config(&config(&path)).unwrap();
+        assert_eq!(verifier.key_count(), 1);
+        assert!(rejected(&verifier, &sign(Algorithm::HS256, "hmac-1", &claims())).contains("unknown signing key"));
+        fs::remove_file(&path).unwrap();
+
+        // 쓸 수 있는 키가 하나도 없으면 기동하지 않는다.
+        let path = write_jwks("hmac-only", &[hmac_jwk("hmac-1")], 1_000);
+        assert!(OidcVerifier::from_config(&config(&path)).is_err());
+        fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn jwks_is_reloaded_when_mtime_changes_and_bad_files_keep_old_keys() {
+        let path = write_jwks("reload", &[rsa_jwk("rsa-1", "RS256")], 1_000);
+        let verifier = OidcVerifier::from_config(&config(&path)).unwrap();
+        let rotated = sign(Algorithm::RS256, "rsa-2", &claims());
+
+        // 내용이 바뀌어도 mtime이 같으면 다시 읽지 않는다.
+        write_jwks("reload", &[rsa_jwk("rsa-2", "RS256")], 1_000);
+        assert!(rejected(&verifier, &rotated).contains("unknown signing key"));
+
+        set_mtime(&path, 2_000);
+        assert!(verifier.verify(&rotated).is_ok());
+        assert!(rejected(&verifier, &sign(Algorithm::RS256, "rsa-1", &claims())).contains("unknown signing key"));
+
+        // 잘못된 파일이나 쓸 수 있는 키가 없는 파일로 바뀌면 기존 키를 유지한다.
+        fs::write(&path, "{ not json").unwrap();
+        set_mtime(&path, 3_000);
+        assert!(verifier.verify(&rotated).is_ok());
+
+        write_jwks("reload", &[hmac_jwk("hmac-1")], 4_000);
+        assert!(verifier.verify(&rotated).is_ok());
+        assert_eq!(verifier.key_count(), 1);
+        fs::remove_file(path).unwrap();
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn unknown_kid_forces_at_most_one_reload_per_interval() {
+        let path = write_jwks("forced", &[rsa_jwk("rsa-1", "RS256")], 1_000);
+        let mut config = config(&path);
+        config.reload_check_secs = 60;
+        let verifier = OidcVerifier::from_config(&config).unwrap();
+
+        // 간격 전이라도 처음 보는 kid는 바로 다시 확인한다. (키 rotation)
+        write_jwks("forced", &[rsa_jwk("rsa-2", "RS256")], 2_000);
+        assert!(verifier.verify(&sign(Algorithm::RS256, "rsa-2", &claims())).is_ok());
+
+        // 모르는 kid가 계속 와도 간격 안에서는 다시 확인하지 않는다.
+        write_jwks("forced", &[rsa_jwk("rsa-3", "RS256")], 3_000);
+        let rotated = sign(Algorithm::RS256, "rsa-3", &claims());
+        for _ in 0..3 {
+            assert!(rejected(&verifier, &rotated).contains("unknown signing key"));
+        }
+        assert!(verifier.verify(&sign(Algorithm::RS256, "rsa-2", &claims())).is_ok());
+
+        tokio::time::advance(Duration::from_secs(30)).await;
+        assert!(rejected(&verifier, &rotated).contains("unknown signing key"));
+
+        tokio::time::advance(Duration::from_secs(30)).await;
+        assert!(verifier.verify(&rotated).is_ok());
+        fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn role_is_resolved_from_nested_claims_through_role_map() {
+        let path = write_jwks("roles", &[rsa_jwk("rsa-1", "RS256")], 1_000);
+        let mut config = config(&path);
+        config.role_claims = vec!["realm_access.roles".to_string(), "groups".to_string()];
+        config.role_map = HashMap::from([
+            ("robot-admins".to_string(), "admin".to_string()),
+            ("robot-operators".to_string(), "operator".to_string()),
+        ]);
+        let verifier = OidcVerifier::from_config(&config).unwrap();
+        let role = |claims: Value| verifier.map_role(claims.as_object().unwrap());
+
+        // role_claims 순서대로, role_map에 있는 첫 값
+        assert_eq!(
+            role(json!({ "realm_access": { "roles": ["offline_access", "robot-operators"] }, "groups": "robot-admins" })),
+            Some("operator".to_string())
+        );
+        assert_eq!(
+            role(json!({ "realm_access": { "roles": ["offline_access"] }, "groups": ["robot-admins"] })),
+            Some("admin".to_string())
+        );
+        assert_eq!(role(json!({ "realm_access": "robot-admins", "roles": ["robot-admins"] })), None);
+
+        // role_map이 비어 있으면 claim 값을 그대로 쓴다.
+        config.role_map.clear();
+        let verifier = OidcVerifier::from_config(&config).unwrap();
+        let claims = json!({ "realm_access": { "roles": ["robot-operators"] } });
+        assert_eq!(verifier.map_role(claims.as_object().unwrap()), Some("robot-operators".to_string()));
+        fs::remove_file(path).unwrap();
+    }
+}
@@ -0,0 +1,374 @@
+use std::collections::{HashMap, HashSet};
+use std::fs;
+
+use anyhow::{anyhow, Context};
+use serde::Deserialize;
+use serde_json::Value;
+
+use crate::auth::Identity;
+use crate::domain::control::ControlRequestType;
+
+/// 권한 정책 파일 형식 (JSON)
+///
+/// ```json
+/// {
+///   "default_role": "viewer",
+///   "roles": {
+///     "viewer":   [{ "robots": ["*"], "screen": true }],
+///     "operator": [{ "robots": ["robot-*"], "screen": true, "commands": ["move", "stop", "velocity"] }],
+///     "admin":    [{ "robots": ["*"], "screen": true, "commands": ["*"] }]
+///   },
+///   "users": {
+///     "alice": [{ "robots": ["lab-01"], "commands": ["dock", "undock"] }]
+///   }
+/// }
+/// ```
+#[derive(Debug, Deserialize)]
+struct PolicyFile {
+    /// role이 없는(또는 토큰에 role claim이 없는) 클라이언트가 쓰는 role
+    #[serde(default)]
+    default_role: Option<String>,
+    /// role -> 허용 목록
+    #[serde(default)]
+    roles: HashMap<String, Vec<GrantDef>>,
+    /// 사용자(sub) -> role 허용 목록에 더해지는 허용 목록
+    #[serde(default)]
+    users: HashMap<String, Vec<GrantDef>>,
+}
+
+#[derive(Debug, Deserialize)]
+struct GrantDef {
+    /// robot_id glob (`*`, `?`)
+    #[serde(default = "any_robot")]
+    robots: Vec<String>,
+    /// /ws/screen 접속 허용
+    #[serde(default)]
+    screen: bool,
+    /// 허용하는 control 명령 (`"*"`는 전부). 하나라도 있으면 /ws/control 접속이 허용된다.
+    #[serde(default)]
+    commands: Vec<String>,
+}
+
+fn any_robot() -> Vec<String> {
+    vec!["*".to_string()]
+}
+
+#[derive(Debug)]
+enum CommandSet {
+    All,
+    Only(HashSet<ControlRequestType>),
+}
+
+impl CommandSet {
+    fn parse(commands: Vec<String>) -> anyhow::Result<Self> {
+        if commands.iter().any(|c| c == "*") {
+            return Ok(CommandSet::All);
+        }
+        let kinds = commands
+            .into_iter()
+            .map(|c| {
+                serde_json::from_value::<ControlRequestType>(Value::String(c.clone()))
+                    .map_err(|_| anyhow!("unknown command type {c:?}"))
+            })
+            .collect::<anyhow::Result<_>>()?;
+        Ok(CommandSet::Only(kinds))
+    }
+
+    fn is_empty(&self) -> bool {
+        matches!(self, CommandSet::Only(kinds) if kinds.is_empty())
+    }
+
+    fn contains(&self, kind: ControlRequestType) -> bool {
+        match self {
+            CommandSet::All => true,
+            CommandSet::Only(kinds) => kinds.contains(&kind),
+        }
+    }
+}
+
+/// robot glob + 채널/명령 허용 범위 하나
+#[derive(Debug)]
+pub struct Grant {
+    robots: Vec<String>,
+    screen: bool,
+    commands: CommandSet,
+}
+
+impl Grant {
+    pub fn new(robots: Vec<String>, screen: bool, commands: Vec<String>) -> anyhow::Result<Self> {
+        Ok(Self {
+            robots,
+            screen,
+            commands: CommandSet::parse(commands)?,
+        })
+    }
+
+    fn from_def(def: GrantDef) -> anyhow::Result<Self> {
+        Self::new(def.robots, def.screen, def.commands)
+    }
+
+    fn covers(&self, robot_id: &str) -> bool {
+        self.robots.iter().any(|pattern| glob_match(pattern, robot_id))
+    }
+}
+
+/// role/사용자별로 robot과 control 명령 접근을 허용하는 정책.
+/// 파일을 지정하지 않으면 모든 클라이언트에게 모든 것을 허용한다. (e_stop은 항상 허용)
+#[derive(Debug, Default)]
+pub struct AccessPolicy {
+    enabled: bool,
+    default_role: Option<String>,
+    roles: HashMap<String, Vec<Grant>>,
+    users: HashMap<String, Vec<Grant>>,
+}
+
+impl AccessPolicy {
+    pub fn load(path: &str) -> anyhow::Result<Self> {
+        let raw = fs::read_to_string(path).with_context(|| format!("failed to read access policy {path}"))?;
+        let file: PolicyFile =
+            serde_json::from_str(&raw).with_context(|| format!("failed to parse access policy {path}"))?;
+        Self::from_file(file, path)
+    }
+
+    fn from_file(file: PolicyFile, path: &str) -> anyhow::Result<Self> {
+        let roles = compile_grants(file.roles).with_context(|| format!("invalid role grant in {path}"))?;
+        let users = compile_grants(file.users).with_context(|| format!("invalid user grant in {path}"))?;
+        if let Some(role) = &file.default_role
+            && !roles.contains_key(role)
+        {
+            return Err(anyhow!("default_role {role:?} is not defined in {path}"));
+        }
+
+        Ok(Self {
+            enabled: true,
+            default_role: file.default_role,
+            roles,
+            users,
+        })
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.enabled
+    }
+
+    pub fn role_count(&self) -> usize {
+        self.roles.len()
+    }
+
+    /// 이 클라이언트에 적용되는 허용 목록 (role 또는 default_role + 사용자별)
+    fn grants_for<'a>(&'a self, identity: &'a Identity) -> impl Iterator<Item = &'a Grant> + 'a {
+        let role = identity.role.as_ref().or(self.default_role.as_ref());
+        let role_grants = role.and_then(|r| self.roles.get(r)).into_iter().flatten();
+        let user_grants = self.users.get(&identity.subject).into_iter().flatten();
+        role_grants.chain(user_grants)
+    }
+
+    /// API key처럼 신원에 범위가 붙어 있으면 그 범위만, 아니면 정책 파일의 허용 목록을 본다.
+    fn allows(&self, identity: &Identity, check: impl Fn(&Grant) -> bool) -> bool {
+        if let Some(scope) = &identity.scope {
+            return check(scope);
+        }
+        !self.enabled || self.grants_for(identity).any(check)
+    }
+
+    /// /ws/screen/{robot_id} 접속 허용 여부
+    pub fn can_view(&self, identity: &Identity, robot_id: &str) -> bool {
+        self.allows(identity, |g| g.screen && g.covers(robot_id))
+    }
+
+    /// /ws/control/{robot_id} 접속 허용 여부 (허용된 명령이 하나라도 있어야 한다)
+    pub fn can_control(&self, identity: &Identity, robot_id: &str) -> bool {
+        self.allows(identity, |g| !g.commands.is_empty() && g.covers(robot_id))
+    }
+
+    /// control 명령 허용 여부
+    pub fn can_send(&self, identity: &Identity, robot_id: &str, kind: ControlRequestType) -> bool {
+        kind.is_policy_exempt() || self.allows(identity, |g| g.commands.contains(kind) && g.covers(robot_id))
+    }
+}
+
+fn compile_grants(defs: HashMap<String, Vec<GrantDef>>) -> anyhow::Result<HashMap<String, Vec<Grant>>> {
+    defs.into_iter()
+        .map(|(name, grants)| {
+            let grants = grants
+                .into_iter()
+                .map(Grant::from_def)
+                .collect::<anyhow::Result<Vec<_>>>()
+                .with_context(|| format!("grant for {name:?}"))?;
+            Ok((name, grants))
+        })
+        .collect()
+}
+
+/// `*`(0개 이상), `?`(정확히 1개)만 지원하는 glob
+fn glob_match(pattern: &str, text: &str) -> bool {
+    let pattern: Vec<char> = pattern.chars().collect();
+    let text: Vec<char> = text.chars().collect();
+    let (mut p, mut t) = (0, 0);
+    // 마지막 `*`의 위치와 그 `*`가 삼킨 text 위치 (backtracking용)
+    let mut star: Option<(usize, usize)> = None;
+
+    while t < text.len() {
+        if p < pattern.len() && (pattern[p] == '?' || pattern[p] == text[t]) {
+            p += 1;
+            t += 1;
+        } else if p < pattern.len() && pattern[p] == '*' {
+            star = Some((p, t));
+            p += 1;
+        } else if let Some((star_p, star_t)) = star {
+            p = star_p + 1;
+            t = star_t + 1;
+            star = Some((star_p, star_t + 1));
+        } else {
+            return false;
+        }
+    }
+    pattern[p..].iter().all(|&c| c == '*')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Arc;
+
+    use serde_json::json;
+
+    fn policy(file: Value) -> AccessPolicy {
+        AccessPolicy::from_file(serde_json::from_value(file).unwrap(), "test").unwrap()
+    }
+
+    fn sample() -> AccessPolicy {
+        policy(json!({
+            "default_role": "viewer",
+            "roles": {
+                "viewer": [{ "robots": ["*"], "screen": true }],
+                "operator": [{ "robots": ["robot-*"], "screen": true, "commands": ["move", "stop", "velocity"] }],
+                "admin": [{ "robots": ["*"], "screen": true, "commands": ["*"] }]
+            },
+            "users": {
+                "alice": [{ "robots": ["lab-01"], "commands": ["dock", "undock"] }]
+            }
+        }))
+    }
+
+    fn user(subject: &str, role: Option<&str>) -> Identity {
+        Identity {
+            subject: subject.to_string(),
+            role: role.map(str::to_string),
+            method: "jwt",
+            key_id: None,
+            scope: None,
+        }
+    }
+
+    fn api_key(role: Option<&str>, robots: &[&str], screen: bool, commands: &[&str]) -> Identity {
+        let scope = Grant::new(
+            robots.iter().map(|r| r.to_string()).collect(),
+            screen,
+            commands.iter().map(|c| c.to_string()).collect(),
+        )
+        .unwrap();
+        Identity {
+            method: "api_key",
+            key_id: Some("scheduler".to_string()),
+            scope: Some(Arc::new(scope)),
+            ..user("scheduler", role)
+        }
+    }
+
+    #[test]
+    fn glob_matches_star_and_question_mark() {
+        assert!(glob_match("robot-01", "robot-01"));
+        assert!(!glob_match("robot-01", "robot-02"));
+        assert!(glob_match("*", ""));
+        assert!(glob_match("robot-*", "robot-"));
+        assert!(glob_match("*-01", "lab-robot-01"));
+        assert!(glob_match("robot-?1", "robot-21"));
+        assert!(!glob_match("robot-??", "robot-1"));
+        assert!(!glob_match("?", ""));
+        assert!(!glob_match("", "robot-01"));
+    }
+
+    #[test]
+    fn glob_backtracks_after_a_partial_match() {
+        // 첫 번째로 맞는 위치에서 실패하면 `*`가 더 삼킨 뒤 다시 맞춰 본다.
+        assert!(glob_match("a*b*c", "aXbYbZc"));
+        assert!(glob_match("*a", "aaa"));
+        assert!(glob_match("lab-*-arm", "lab-1-arm-2-arm"));
+        assert!(glob_match("*?-01", "x-01"));
+        assert!(!glob_match("lab-*-arm", "lab-1-arm-2"));
+        assert!(!glob_match("*a*b", "aaaa"));
+        assert!(glob_match("로봇-*", "로봇-01"));
+    }
+
+    #[test]
+    fn client_without_role_falls_back_to_default_role() {
+        let policy = sample();
+        let anonymous = user("bob", None);
+        assert!(policy.can_view(&anonymous, "robot-01"));
+        assert!(!policy.can_control(&anonymous, "robot-01"));
+        assert!(!policy.can_send(&anonymous, "robot-01", ControlRequestType::Move));
+
+        // role이 있으면 그 role만 본다 (정의되지 않은 role은 default_role로 대체되지 않는다)
+        assert!(!policy.can_view(&user("bob", Some("ghost")), "robot-01"));
+        assert!(policy.can_send(&user("bob", Some("operator")), "robot-01", ControlRequestType::Move));
+        assert!(!policy.can_send(&user("bob", Some("operator")), "lab-01", ControlRequestType::Move));
+    }
+
+    #[test]
+    fn user_grants_are_added_to_role_grants() {
+        let policy = sample();
+        let alice = user("alice", Some("operator"));
+        assert!(policy.can_send(&alice, "robot-01", ControlRequestType::Move));
+        assert!(policy.can_control(&alice, "lab-01"));
+        assert!(policy.can_send(&alice, "lab-01", ControlRequestType::Dock));
+        assert!(!policy.can_send(&alice, "lab-01", ControlRequestType::Move));
+        assert!(!policy.can_send(&alice, "robot-01", ControlRequestType::Dock));
+
+        let bob = user("bob", Some("operator"));
+        assert!(!policy.can_control(&bob, "lab-01"));
+        assert!(!policy.can_send(&bob, "lab-01", ControlRequestType::Dock));
+    }
+
+    #[test]
+    fn api_key_scope_overrides_the_policy_file() {
+        let policy = sample();
+        // admin role이어도 key의 범위 밖은 허용하지 않는다.
+        let key = api_key(Some("admin"), &["robot-01"], false, &["move", "stop"]);
+        assert!(policy.can_send(&key, "robot-01", ControlRequestType::Move));
+        assert!(!policy.can_send(&key, "robot-01", ControlRequestType::Dock));
+        assert!(!policy.can_send(&key, "robot-02", ControlRequestType::Move));
+        assert!(!policy.can_view(&key, "robot-01"));
+
+        // 정책 파일이 없어도 key의 범위는 적용된다.
+        let open = AccessPolicy::default();
+        assert!(!open.is_enabled());
+        assert!(!open.can_send(&key, "robot-02", ControlRequestType::Move));
+        assert!(open.can_send(&user("bob", None), "robot-02", ControlRequestType::Dock));
+    }
+
+    #[test]
+    fn e_stop_is_allowed_for_every_identity() {
+        let policy = sample();
+        let identities = [
+            user("bob", None),
+            user("bob", Some("ghost")),
+            user("bob", Some("viewer")),
+            api_key(None, &["other-*"], false, &["move"]),
+            api_key(None, &[], false, &[]),
+        ];
+        for identity in &identities {
+            assert!(policy.can_send(identity, "robot-01", ControlRequestType::EStop), "{identity}");
+            assert!(!policy.can_send(identity, "robot-01", ControlRequestType::ResetEstop), "{identity}");
+        }
+    }
+
+    #[test]
+    fn invalid_policy_is_rejected() {
+        let undefined_default = json!({ "default_role": "ghost", "roles": {} });
+        assert!(AccessPolicy::from_file(serde_json::from_value(undefined_default).unwrap(), "test").is_err());
+
+        let unknown_command = json!({ "roles": { "operator": [{ "commands": ["fly"] }] } });
+        assert!(AccessPolicy::from_file(serde_json::from_value(unknown_command).unwrap(), "test").is_err());
+    }
+}
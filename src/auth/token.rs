@@ -0,0 +1,111 @@
+use tokio_tungstenite::tungstenite::handshake::server::Request;
+use tokio_tungstenite::tungstenite::http::header::{AUTHORIZATION, SEC_WEBSOCKET_PROTOCOL};
+
+/// 브라우저는 WebSocket에 Authorization header를 붙일 수 없으므로
+/// `Sec-WebSocket-Protocol: bearer, <token>` 형태로도 받는다.
+pub const BEARER_PROTOCOL: &str = "bearer";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TokenSource {
+    Header,
+    Subprotocol,
+    Query,
+}
+
+/// handshake 요청에서 API key를 꺼낸다.
+pub fn extract_api_key(req: &Request, header: &str) -> Option<String> {
+    req.headers()
+        .get(header)
+        .and_then(|v| v.to_str().ok())
+        .map(str::trim)
+        .filter(|v| !v.is_empty())
+        .map(str::to_string)
+}
+
+/// handshake 요청에서 토큰을 꺼낸다.
+pub fn extract_token(req: &Request, query_param: &str) -> Option<(String, TokenSource)> {
+    if let Some(token) = req
+        .headers()
+        .get(AUTHORIZATION)
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.strip_prefix("Bearer ").or_else(|| v.strip_prefix("bearer ")))
+    {
+        return Some((token.trim().to_string(), TokenSource::Header));
+    }
+
+    if let Some(protocols) = req.headers().get(SEC_WEBSOCKET_PROTOCOL).and_then(|v| v.to_str().ok()) {
+        let entries: Vec<&str> = protocols.split(',').map(str::trim).collect();
+        if entries.contains(&BEARER_PROTOCOL)
+            && let Some(token) = entries.iter().find(|p| **p != BEARER_PROTOCOL && !p.is_empty())
+        {
+            return Some((token.to_string(), TokenSource::Subprotocol));
+        }
+    }
+
+    // JWT는 base64url + '.' 이라 percent-decoding 없이 그대로 쓴다.
+    req.uri()
+        .query()?
+        .split('&')
+        .filter_map(|pair| pair.split_once('='))
+        .find(|(key, value)| *key == query_param && !value.is_empty())
+        .map(|(_, value)| (value.to_string(), TokenSource::Query))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn request(uri: &str, headers: &[(&str, &str)]) -> Request {
+        let mut builder = Request::builder().uri(uri);
+        for (name, value) in headers {
+            builder = builder.header(*name, *value);
+        }
+        builder.body(()).unwrap()
+    }
+
+    fn token(req: &Request) -> Option<(String, TokenSource)> {
+        extract_token(req, "access_token")
+    }
+
+    #[test]
+    fn token_from_authorization_header() {
+        let req = request("/control", &[("Authorization", "Bearer abc.def.ghi")]);
+        assert_eq!(token(&req), Some(("abc.def.ghi".to_string(), TokenSource::Header)));
+
+        let req = request("/control", &[("Authorization", "Basic dXNlcjpwdw==")]);
+        assert_eq!(token(&req), None);
+    }
+
+    #[test]
+    fn token_from_bearer_subprotocol() {
+        let req = request("/control", &[("Sec-WebSocket-Protocol", "bearer, abc.def.ghi")]);
+        assert_eq!(token(&req), Some(("abc.def.ghi".to_string(), TokenSource::Subprotocol)));
+
+        // bearer가 없으면 다른 subprotocol을 토큰으로 보지 않는다.
+        let req = request("/control", &[("Sec-WebSocket-Protocol", "chat, abc.def.ghi")]);
+        assert_eq!(token(&req), None);
+    }
+
+    #[test]
+    fn token_from_query_param() {
+        let req = request("/control?robot_id=robot-01&access_token=abc.def.ghi", &[]);
+        assert_eq!(token(&req), Some(("abc.def.ghi".to_string(), TokenSource::Query)));
+
+        let req = request("/control?access_token=", &[]);
+        assert_eq!(token(&req), None);
+        assert_eq!(extract_token(&request("/control?token=abc", &[]), "access_token"), None);
+    }
+
+    #[test]
+    fn header_takes_precedence_over_query() {
+        let req = request("/control?access_token=from-query", &[("Authorization", "Bearer from-header")]);
+        assert_eq!(token(&req), Some(("from-header".to_string(), TokenSource::Header)));
+    }
+
+    #[test]
+    fn api_key_from_header() {
+        let req = request("/control", &[("x-api-key", "  secret-key ")]);
+        assert_eq!(extract_api_key(&req, "x-api-key").as_deref(), Some("secret-key"));
+        assert_eq!(extract_api_key(&request("/control", &[]), "x-api-key"), None);
+    }
+}
@@ -1,32 +1,396 @@
 use serde::Deserialize;
 use config::{Config, File as ConfigFile};
+use std::collections::HashMap;
 use std::env;
+use std::fmt;
+use std::str::FromStr;
+
+use anyhow::Context;
+
+use crate::domain::control::ControlRequestType;
 
 #[derive(Deserialize, Debug)]
 pub struct WebsocketConfig {
     pub self_ip: String,
     pub self_port: String,
+
+    /// viewer(screen 세션)마다 쌓아둘 수 있는 signaling 메시지 수. 넘치면 느린 viewer로 보고 끊는다.
+    #[serde(default = "default_viewer_queue_capacity")]
+    pub viewer_queue_capacity: usize,
+}
+
+fn default_viewer_queue_capacity() -> usize {
+    64
+}
+
+/// gRPC signaling 스트림 운용 방식
+#[derive(Deserialize, Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum SignalStreamMode {
+    /// 모든 robot이 하나의 OpenSignalStream 호출을 공유한다.
+    #[default]
+    Shared,
+    /// robot_id마다 별도의 bi-di 스트림을 연다. (재연결/inbound routing도 robot별로 독립)
+    PerRobot,
+}
+
+impl std::str::FromStr for SignalStreamMode {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "shared" => Ok(SignalStreamMode::Shared),
+            "per_robot" => Ok(SignalStreamMode::PerRobot),
+            other => Err(anyhow::anyhow!("unknown stream_mode: {other} (expected shared | per_robot)")),
+        }
+    }
 }
 
 #[derive(Deserialize, Debug)]
 pub struct GRPCConfig {
     pub to_ip: String,
     pub to_port: String,
+
+    #[serde(default)]
+    pub stream_mode: SignalStreamMode,
+
+    /// 스트림마다 outbound control/signaling lane에 쌓아둘 수 있는 메시지 수.
+    /// (e_stop/stop은 제한 없음, move/set_speed/velocity는 가장 오래된 것부터 버림, 나머지는 거절)
+    #[serde(default = "default_outbound_queue_capacity")]
+    pub outbound_queue_capacity: usize,
+
+    /// true면 아직 전송되지 않은 같은 robot의 move/set_speed/velocity를 최신 값으로 덮어쓴다.
+    /// dock, path_follow 등 일반 명령은 덮어쓰지 않는다.
+    #[serde(default)]
+    pub coalesce_motion: bool,
+}
+
+fn default_outbound_queue_capacity() -> usize {
+    256
+}
+
+/// 상한을 넘는 속도를 어떻게 처리할지
+#[derive(Deserialize, Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum SpeedPolicy {
+    /// 상한으로 잘라서 전달
+    Clamp,
+    /// control_error로 거절
+    #[default]
+    Reject,
+}
+
+impl std::str::FromStr for SpeedPolicy {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "clamp" => Ok(SpeedPolicy::Clamp),
+            "reject" => Ok(SpeedPolicy::Reject),
+            other => Err(anyhow::anyhow!("unknown speed_policy: {other} (expected clamp | reject)")),
+        }
+    }
+}
+
+/// 제어 명령 속도 상한. 실제 상한은 max_speed, robot별, role별 값 중 가장 작은 값이다.
+#[derive(Deserialize, Debug, Clone)]
+#[serde(default)]
+pub struct SpeedLimits {
+    pub max_speed: f64,
+    /// velocity 명령의 |angular_z| 상한 (rad/s). speed_policy가 같이 적용된다.
+    pub max_angular_speed: f64,
+    /// 시간 제한 move의 duration_ms 상한. 넘으면 speed_policy에 따라 잘라내거나 거절한다.
+    pub max_move_duration_ms: u64,
+    pub speed_policy: SpeedPolicy,
+    /// robot_id -> 상한
+    pub robots: HashMap<String, f64>,
+    /// role -> 상한
+    pub roles: HashMap<String, f64>,
+}
+
+impl Default for SpeedLimits {
+    fn default() -> Self {
+        Self {
+            max_speed: 2.0,
+            max_angular_speed: 2.0,
+            max_move_duration_ms: 10_000,
+            speed_policy: SpeedPolicy::default(),
+            robots: HashMap::new(),
+            roles: HashMap::new(),
+        }
+    }
+}
+
+impl SpeedLimits {
+    pub fn cap_for(&self, robot_id: &str, role: Option<&str>) -> f64 {
+        let robot_cap = self.robots.get(robot_id).copied().unwrap_or(f64::INFINITY);
+        let role_cap = role
+            .and_then(|r| self.roles.get(r).copied())
+            .unwrap_or(f64::INFINITY);
+        self.max_speed.min(robot_cap).min(role_cap)
+    }
+}
+
+/// token bucket 설정: 초당 rate개씩 채워지고 최대 burst개까지 한 번에 보낼 수 있다.
+#[derive(Deserialize, Debug, Clone, Copy)]
+pub struct RateBucket {
+    pub rate: f64,
+    pub burst: f64,
+}
+
+/// 제어 명령 전송 한도 ([control.rate_limit]). 설정하지 않은 항목은 제한 없음, e_stop은 항상 제외.
+#[derive(Deserialize, Debug, Clone, Default)]
+#[serde(default)]
+pub struct RateLimitConfig {
+    /// control 세션 하나가 보내는 전체 명령
+    pub session: Option<RateBucket>,
+    /// 한 robot으로 가는 전체 명령 (모든 control 세션 합산)
+    pub robot: Option<RateBucket>,
+    /// 명령 종류별 (세션 단위), 예: move = { rate = 30.0, burst = 30.0 }
+    pub commands: HashMap<ControlRequestType, RateBucket>,
+}
+
+/// control 채널 동작 설정 (배포 환경별로 조정)
+#[derive(Deserialize, Debug, Clone)]
+#[serde(default)]
+pub struct ControlConfig {
+    /// control 세션이 비정상 종료(소켓 에러, pong 없음, 프로세스 종료)되면 robot에 STOP을 보낸다.
+    pub stop_on_disconnect: bool,
+    /// ping을 보낸 뒤 이 시간 안에 pong이 없으면 세션을 끊는다.
+    pub pong_timeout_secs: u64,
+    /// 0보다 크면 heartbeat watchdog 모드: 이 시간 안에 heartbeat(또는 새 move)가 없으면
+    /// robot에 STOP, 클라이언트에 control_error를 보낸다.
+    pub heartbeat_timeout_ms: u64,
+    /// true면 robot마다 한 operator만 lease를 잡고 제어할 수 있다. (e_stop은 누구나 가능)
+    pub exclusive_lease: bool,
+    /// lease 보유자가 이 시간 동안 명령을 보내지 않으면 lease가 만료된다.
+    pub lease_ttl_secs: u64,
+    /// ack: "robot" 명령이 이 시간 안에 CommandResult를 받지 못하면 control_error를 보낸다.
+    pub command_ack_timeout_ms: u64,
+    /// 0보다 크면 timestamp_ms가 이보다 오래된 요청은 expired로 거절한다. (e_stop 제외)
+    pub max_command_age_ms: u64,
+    /// reset_estop을 보낼 수 있는 role. 인증을 켠 경우에만 적용되고, 비어 있으면 아무도 latch를 풀 수 없다.
+    pub estop_reset_roles: Vec<String>,
+    /// 인증을 끈 경우에도 reset_estop을 허용한다. (control 채널에 붙은 누구나 latch를 풀 수 있으므로 개발용)
+    pub allow_unauthenticated_estop_reset: bool,
+    /// 속도 상한 ([control.limits])
+    pub limits: SpeedLimits,
+    /// 전송 한도 ([control.rate_limit])
+    pub rate_limit: RateLimitConfig,
+    /// vendor 명령 카탈로그 파일 (JSON). 비어 있으면 vendor 명령은 모두 거절된다.
+    pub vendor_catalog_path: String,
+}
+
+impl ControlConfig {
+    /// 기본은 거절: estop_reset_roles의 role만 풀 수 있다. 인증을 끄면 신원을 구분할 수 없으므로
+    /// allow_unauthenticated_estop_reset을 켠 경우에만 허용한다.
+    pub fn can_reset_estop(&self, auth_enabled: bool, role: Option<&str>) -> bool {
+        if !auth_enabled {
+            return self.allow_unauthenticated_estop_reset;
+        }
+        role.is_some_and(|r| self.estop_reset_roles.iter().any(|allowed| allowed == r))
+    }
+}
+
+impl Default for ControlConfig {
+    fn default() -> Self {
+        Self {
+            stop_on_disconnect: true,
+            pong_timeout_secs: 45,
+            heartbeat_timeout_ms: 0,
+            exclusive_lease: false,
+            lease_ttl_secs: 30,
+            command_ack_timeout_ms: 3000,
+            max_command_age_ms: 0,
+            estop_reset_roles: Vec::new(),
+            allow_unauthenticated_estop_reset: false,
+            limits: SpeedLimits::default(),
+            rate_limit: RateLimitConfig::default(),
+            vendor_catalog_path: String::new(),
+        }
+    }
+}
+
+/// JWT 서명 알고리즘
+#[derive(Deserialize, Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum JwtAlgorithm {
+    /// shared secret (jwt_key_file = secret 파일)
+    #[default]
+    #[serde(rename = "HS256")]
+    Hs256,
+    /// RSA 공개키 (jwt_key_file = PEM 파일)
+    #[serde(rename = "RS256")]
+    Rs256,
+}
+
+impl std::str::FromStr for JwtAlgorithm {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "HS256" => Ok(JwtAlgorithm::Hs256),
+            "RS256" => Ok(JwtAlgorithm::Rs256),
+            other => Err(anyhow::anyhow!("unknown jwt_algorithm: {other} (expected HS256 | RS256)")),
+        }
+    }
+}
+
+/// 기계 클라이언트용 정적 API key ([[auth.api_keys]])
+#[derive(Deserialize, Debug, Clone)]
+pub struct ApiKeyConfig {
+    /// 로그/감사 기록에 남는 key 이름
+    pub id: String,
+    /// key 원문의 sha256 (hex). 원문은 config에 두지 않는다.
+    pub sha256: String,
+    /// 속도 상한/reset_estop 판단에 쓰는 role
+    #[serde(default)]
+    pub role: Option<String>,
+    /// 허용하는 robot_id glob
+    #[serde(default = "default_api_key_robots")]
+    pub robots: Vec<String>,
+    /// /ws/screen 접속 허용
+    #[serde(default)]
+    pub screen: bool,
+    /// 허용하는 control 명령 (`"*"`는 전부)
+    #[serde(default)]
+    pub commands: Vec<String>,
+}
+
+fn default_api_key_robots() -> Vec<String> {
+    vec!["*".to_string()]
+}
+
+/// SSO가 발급한 OIDC token 검증 ([auth.oidc])
+#[derive(Deserialize, Debug, Clone)]
+#[serde(default)]
+pub struct OidcConfig {
+    /// 서명 키 JWKS 파일. 비어 있으면 OIDC 검증을 쓰지 않는다.
+    pub jwks_path: String,
+    /// iss claim과 일치해야 한다. (필수)
+    pub issuer: String,
+    /// aud claim에 포함돼야 한다. (필수)
+    pub audience: String,
+    /// JWKS 파일 변경(mtime)을 확인하는 최소 간격. 모르는 kid가 오면 바로 확인하되, 그것도 이 간격에 한 번만 한다.
+    pub reload_check_secs: u64,
+    /// role을 찾을 claim (순서대로, `realm_access.roles`처럼 중첩 가능)
+    pub role_claims: Vec<String>,
+    /// claim 값 -> gateway role. 비어 있으면 claim 값을 그대로 role로 쓴다.
+    pub role_map: HashMap<String, String>,
+}
+
+impl Default for OidcConfig {
+    fn default() -> Self {
+        Self {
+            jwks_path: String::new(),
+            issuer: String::new(),
+            audience: String::new(),
+            reload_check_secs: 10,
+            role_claims: vec!["roles".to_string()],
+            role_map: HashMap::new(),
+        }
+    }
+}
+
+/// WebSocket handshake 인증 설정 ([auth])
+#[derive(Deserialize, Debug, Clone)]
+#[serde(default)]
+pub struct AuthConfig {
+    /// false면 인증 없이 모든 연결을 받는다.
+    pub enabled: bool,
+    pub jwt_algorithm: JwtAlgorithm,
+    /// HS256이면 secret 파일, RS256이면 공개키 PEM 파일
+    pub jwt_key_file: String,
+    /// 비어 있지 않으면 iss claim이 일치해야 한다.
+    pub issuer: String,
+    /// 비어 있지 않으면 aud claim이 일치해야 한다.
+    pub audience: String,
+    /// 토큰을 담는 query parameter 이름 (header를 못 쓰는 클라이언트용)
+    pub query_param: String,
+    /// role로 쓸 claim 이름
+    pub role_claim: String,
+    /// role/사용자별 robot·명령 권한 파일 (JSON). 비어 있으면 모든 클라이언트에게 모두 허용한다.
+    pub policy_path: String,
+    /// API key를 담는 header 이름
+    pub api_key_header: String,
+    /// 정적 API key. key마다 robot/명령 범위가 정해지고, 권한 정책 파일 대신 이 범위가 적용된다.
+    pub api_keys: Vec<ApiKeyConfig>,
+    /// API key 목록 파일 (JSON 배열, 항목 형식은 api_keys와 같음). api_keys에 더해진다.
+    /// docker처럼 config 파일을 직접 고칠 수 없는 배포용
+    pub api_keys_file: String,
+    /// OIDC token 검증 (JWKS)
+    pub oidc: OidcConfig,
+}
+
+impl Default for AuthConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            jwt_algorithm: JwtAlgorithm::default(),
+            jwt_key_file: String::new(),
+            issuer: String::new(),
+            audience: String::new(),
+            query_param: "access_token".to_string(),
+            role_claim: "role".to_string(),
+            policy_path: String::new(),
+            api_key_header: "x-api-key".to_string(),
+            api_keys: Vec::new(),
+            api_keys_file: String::new(),
+            oidc: OidcConfig::default(),
+        }
+    }
+}
+
+/// 큐 깊이/드롭 카운터 로그 설정
+#[derive(Deserialize, Debug, Clone)]
+#[serde(default)]
+pub struct MetricsConfig {
+    /// 0보다 크면 이 주기(초)마다 `[metrics]` 로그를 남긴다.
+    pub log_interval_secs: u64,
+}
+
+impl Default for MetricsConfig {
+    fn default() -> Self {
+        Self { log_interval_secs: 60 }
+    }
 }
 
 #[derive(Deserialize, Debug)]
 pub struct Settings {
     pub websocket_server: WebsocketConfig,
     pub grpc_client: GRPCConfig,
+
+    #[serde(default)]
+    pub control: ControlConfig,
+
+    #[serde(default)]
+    pub metrics: MetricsConfig,
+
+    #[serde(default)]
+    pub auth: AuthConfig,
 }
 
-pub fn load_settings() -> Settings {
+/// 환경변수가 있으면 파싱한다. 파싱에 실패하면 변수 이름과 값을 담아 기동을 멈춘다.
+fn parse_env<T>(name: &str) -> anyhow::Result<Option<T>>
+where
+    T: FromStr,
+    T::Err: fmt::Display,
+{
+    match env::var(name) {
+        Ok(v) => v
+            .trim()
+            .parse()
+            .map(Some)
+            .map_err(|e| anyhow::anyhow!("invalid value {v:?} for environment variable {name}: {e}")),
+        Err(_) => Ok(None),
+    }
+}
+
+pub fn load_settings() -> anyhow::Result<Settings> {
     let mut settings: Settings = Config::builder()
         .add_source(ConfigFile::with_name("config/default").required(true))
         .build()
-        .unwrap()
-        .try_deserialize()
-        .unwrap();
+        .and_then(|config| config.try_deserialize())
+        .context("failed to load config/default")?;
 
     // 환경변수로 덮어쓰기 (docker env 파일 self_ip/self_port/to_ip/to_port)
     if let Ok(v) = env::var("self_ip") {
@@ -41,6 +405,170 @@ pub fn load_settings() -> Settings {
     if let Ok(v) = env::var("to_port") {
         settings.grpc_client.to_port = v;
     }
+    if let Some(v) = parse_env("viewer_queue_capacity")? {
+        settings.websocket_server.viewer_queue_capacity = v;
+    }
+    if let Some(v) = parse_env("stream_mode")? {
+        settings.grpc_client.stream_mode = v;
+    }
+    if let Some(v) = parse_env("outbound_queue_capacity")? {
+        settings.grpc_client.outbound_queue_capacity = v;
+    }
+    if let Some(v) = parse_env("coalesce_motion")? {
+        settings.grpc_client.coalesce_motion = v;
+    }
+    if let Some(v) = parse_env("stop_on_disconnect")? {
+        settings.control.stop_on_disconnect = v;
+    }
+    if let Some(v) = parse_env("pong_timeout_secs")? {
+        settings.control.pong_timeout_secs = v;
+    }
+    if let Some(v) = parse_env("heartbeat_timeout_ms")? {
+        settings.control.heartbeat_timeout_ms = v;
+    }
+    if let Some(v) = parse_env("exclusive_lease")? {
+        settings.control.exclusive_lease = v;
+    }
+    if let Some(v) = parse_env("lease_ttl_secs")? {
+        settings.control.lease_ttl_secs = v;
+    }
+    if let Some(v) = parse_env("command_ack_timeout_ms")? {
+        settings.control.command_ack_timeout_ms = v;
+    }
+    if let Some(v) = parse_env("max_command_age_ms")? {
+        settings.control.max_command_age_ms = v;
+    }
+    if let Ok(v) = env::var("estop_reset_roles") {
+        // 쉼표로 구분 (예: "admin,safety")
+        settings.control.estop_reset_roles = v
+            .split(',')
+            .map(str::trim)
+            .filter(|r| !r.is_empty())
+            .map(str::to_string)
+            .collect();
+    }
+    if let Some(v) = parse_env("allow_unauthenticated_estop_reset")? {
+        settings.control.allow_unauthenticated_estop_reset = v;
+    }
+    if let Ok(v) = env::var("vendor_catalog_path") {
+        settings.control.vendor_catalog_path = v;
+    }
+    if let Some(v) = parse_env("max_speed")? {
+        settings.control.limits.max_speed = v;
+    }
+    if let Some(v) = parse_env("max_angular_speed")? {
+        settings.control.limits.max_angular_speed = v;
+    }
+    if let Some(v) = parse_env("max_move_duration_ms")? {
+        settings.control.limits.max_move_duration_ms = v;
+    }
+    if let Some(v) = parse_env("speed_policy")? {
+        settings.control.limits.speed_policy = v;
+    }
+    if let Some(v) = parse_env("metrics_log_interval_secs")? {
+        settings.metrics.log_interval_secs = v;
+    }
+    if let Some(v) = parse_env("auth_enabled")? {
+        settings.auth.enabled = v;
+    }
+    if let Some(v) = parse_env("jwt_algorithm")? {
+        settings.auth.jwt_algorithm = v;
+    }
+    if let Ok(v) = env::var("jwt_key_file") {
+        settings.auth.jwt_key_file = v;
+    }
+    if let Ok(v) = env::var("jwt_issuer") {
+        settings.auth.issuer = v;
+    }
+    if let Ok(v) = env::var("jwt_audience") {
+        settings.auth.audience = v;
+    }
+    if let Ok(v) = env::var("policy_path") {
+        settings.auth.policy_path = v;
+    }
+    if let Ok(v) = env::var("api_key_header") {
+        settings.auth.api_key_header = v;
+    }
+    if let Ok(v) = env::var("api_keys_file") {
+        settings.auth.api_keys_file = v;
+    }
+    if let Ok(v) = env::var("oidc_jwks_path") {
+        settings.auth.oidc.jwks_path = v;
+    }
+    if let Ok(v) = env::var("oidc_issuer") {
+        settings.auth.oidc.issuer = v;
+    }
+    if let Ok(v) = env::var("oidc_audience") {
+        settings.auth.oidc.audience = v;
+    }
+    if let Some(v) = parse_env("oidc_reload_check_secs")? {
+        settings.auth.oidc.reload_check_secs = v;
+    }
+    if let Ok(v) = env::var("oidc_role_claims") {
+        // 쉼표로 구분 (예: "realm_access.roles,groups")
+        settings.auth.oidc.role_claims = v
+            .split(',')
+            .map(str::trim)
+            .filter(|c| !c.is_empty())
+            .map(str::to_string)
+            .collect();
+    }
+    if let Ok(v) = env::var("oidc_role_map") {
+        // 쉼표로 구분한 claim=role (예: "robot-admins=admin,robot-operators=operator")
+        settings.auth.oidc.role_map = v
+            .split(',')
+            .filter_map(|pair| pair.split_once('='))
+            .map(|(claim, role)| (claim.trim().to_string(), role.trim().to_string()))
+            .filter(|(claim, role)| !claim.is_empty() && !role.is_empty())
+            .collect();
+    }
+
+    Ok(settings)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_env_reports_the_variable_name() {
+        // 다른 테스트와 겹치지 않는 변수 이름만 쓴다.
+        let name = "gateway_test_parse_env_u64";
+        assert!(parse_env::<u64>(name).unwrap().is_none());
+
+        unsafe { env::set_var(name, " 250 ") };
+        assert_eq!(parse_env::<u64>(name).unwrap(), Some(250));
 
-    settings
+        unsafe { env::set_var(name, "fast") };
+        let err = parse_env::<u64>(name).unwrap_err().to_string();
+        assert!(err.contains(name) && err.contains("\"fast\""), "{err}");
+
+        unsafe { env::set_var(name, "sideways") };
+        let err = parse_env::<SpeedPolicy>(name).unwrap_err().to_string();
+        assert!(err.contains("expected clamp | reject"), "{err}");
+
+        unsafe { env::remove_var(name) };
+    }
+
+    #[test]
+    fn reset_estop_is_denied_by_default() {
+        let mut control = ControlConfig {
+            estop_reset_roles: vec!["admin".to_string()],
+            ..ControlConfig::default()
+        };
+        assert!(control.can_reset_estop(true, Some("admin")));
+        assert!(!control.can_reset_estop(true, Some("operator")));
+        assert!(!control.can_reset_estop(true, None));
+        // 인증을 끄면 명시적으로 허용한 경우에만 풀 수 있다.
+        assert!(!control.can_reset_estop(false, None));
+        assert!(!control.can_reset_estop(false, Some("admin")));
+
+        control.allow_unauthenticated_estop_reset = true;
+        assert!(control.can_reset_estop(false, None));
+        // 인증을 켠 경우에는 영향이 없다.
+        assert!(!control.can_reset_estop(true, None));
+
+        control.estop_reset_roles.clear();
+        assert!(!control.can_reset_estop(true, Some("admin")));
+    }
 }
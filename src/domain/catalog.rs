@@ -0,0 +1,349 @@
+use std::collections::HashMap;
+use std::fmt;
+use std::fs;
+
+use anyhow::{anyhow, Context};
+use serde::Deserialize;
+use serde_json::{json, Value};
+
+use crate::domain::signal::ControlPayload;
+
+/// 카탈로그 파일 형식 (JSON)
+///
+/// ```json
+/// {
+///   "default_model": "acme-x1",
+///   "robots": { "robot-01": "acme-x1" },
+///   "models": {
+///     "acme-x1": {
+///       "set_led": { "schema": { "type": "object", "required": ["color"] } }
+///     }
+///   }
+/// }
+/// ```
+#[derive(Debug, Deserialize)]
+struct CatalogFile {
+    /// robots에 없는 robot이 쓰는 model
+    #[serde(default)]
+    default_model: Option<String>,
+    /// robot_id -> model
+    #[serde(default)]
+    robots: HashMap<String, String>,
+    /// model -> (명령 이름 -> 정의)
+    #[serde(default)]
+    models: HashMap<String, HashMap<String, CommandDef>>,
+}
+
+#[derive(Debug, Deserialize)]
+struct CommandDef {
+    /// params가 따라야 하는 JSON Schema (생략하면 아무 object나 허용)
+    #[serde(default = "any_object_schema")]
+    schema: Value,
+}
+
+fn any_object_schema() -> Value {
+    json!({ "type": "object" })
+}
+
+/// 카탈로그 검사 실패
+#[derive(Debug)]
+pub enum CatalogError {
+    /// robot에 연결된 model이 없음
+    UnknownModel { robot_id: String },
+    /// model에 선언되지 않은 명령
+    UnknownCommand { model: String, name: String },
+    /// params가 schema를 만족하지 않음. (instance path, 사유) 목록
+    InvalidParams { name: String, errors: Vec<(String, String)> },
+}
+
+impl CatalogError {
+    /// control_error.details로 내려보낼 값
+    pub fn details(&self) -> Value {
+        match self {
+            CatalogError::UnknownModel { robot_id } => json!({ "robot_id": robot_id }),
+            CatalogError::UnknownCommand { model, name } => json!({ "model": model, "name": name }),
+            CatalogError::InvalidParams { name, errors } => json!({
+                "name": name,
+                "errors": errors
+                    .iter()
+                    .map(|(path, message)| json!({ "path": path, "message": message }))
+                    .collect::<Vec<_>>(),
+            }),
+        }
+    }
+}
+
+impl fmt::Display for CatalogError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CatalogError::UnknownModel { robot_id } => {
+                write!(f, "no vendor command catalog for robot {robot_id}")
+            }
+            CatalogError::UnknownCommand { model, name } => {
+                write!(f, "unknown vendor command {name:?} for model {model}")
+            }
+            CatalogError::InvalidParams { name, errors } => {
+                write!(f, "params for vendor command {name:?} do not match schema ({} error(s))", errors.len())
+            }
+        }
+    }
+}
+
+impl std::error::Error for CatalogError {}
+
+/// config에 선언된 vendor 명령 카탈로그. robot model마다 허용하는 명령 이름과 params schema를 가진다.
+#[derive(Default)]
+pub struct CommandCatalog {
+    default_model: Option<String>,
+    robots: HashMap<String, String>,
+    models: HashMap<String, HashMap<String, jsonschema::Validator>>,
+}
+
+impl CommandCatalog {
+    /// 파일을 읽고 모든 schema를 미리 컴파일한다. schema가 잘못됐으면 기동 시점에 실패한다.
+    pub fn load(path: &str) -> anyhow::Result<Self> {
+        let raw = fs::read_to_string(path).with_context(|| format!("failed to read command catalog {path}"))?;
+        let file: CatalogFile =
+            serde_json::from_str(&raw).with_context(|| format!("failed to parse command catalog {path}"))?;
+
+        let mut models = HashMap::new();
+        for (model, commands) in file.models {
+            let mut validators = HashMap::new();
+            for (name, def) in commands {
+                let validator = jsonschema::validator_for(&def.schema)
+                    .map_err(|e| anyhow!("invalid schema for {model}/{name} in {path}: {e}"))?;
+                validators.insert(name, validator);
+            }
+            models.insert(model, validators);
+        }
+
+        Ok(Self {
+            default_model: file.default_model,
+            robots: file.robots,
+            models,
+        })
+    }
+
+    pub fn command_count(&self) -> usize {
+        self.models.values().map(HashMap::len).sum()
+    }
+
+    pub fn model_for(&self, robot_id: &str) -> Option<&str> {
+        self.robots
+            .get(robot_id)
+            .or(self.default_model.as_ref())
+            .map(String::as_str)
+    }
+
+    /// robot의 model에 이 이름의 명령이 선언돼 있는지
+    pub fn declares(&self, robot_id: &str, name: &str) -> bool {
+        self.model_for(robot_id)
+            .and_then(|model| self.models.get(model))
+            .is_some_and(|commands| commands.contains_key(name))
+    }
+
+    /// vendor 명령은 카탈로그에 선언된 이름인지, params가 schema를 만족하는지 확인하고 model을 채운다.
+    /// custom 명령도 카탈로그에 같은 이름이 있으면 같은 schema로 검사한다. (검증 우회 방지)
+    pub fn check_payload(&self, robot_id: &str, payload: &mut ControlPayload) -> Result<(), CatalogError> {
+        match payload {
+            ControlPayload::Vendor { model, name, params } => self
+                .validate(robot_id, name, &Value::Object(params.clone()))
+                .map(|catalog_model| *model = catalog_model.to_string()),
+            ControlPayload::Custom { name, params } if self.declares(robot_id, name) => self
+                .validate(robot_id, name, &Value::Object(params.clone()))
+                .map(|_| ()),
+            _ => Ok(()),
+        }
+    }
+
+    /// robot의 model에 선언된 명령인지, params가 schema를 만족하는지 검사하고 model 이름을 돌려준다.
+    pub fn validate(&self, robot_id: &str, name: &str, params: &Value) -> Result<&str, CatalogError> {
+        let model = self.model_for(robot_id).ok_or_else(|| CatalogError::UnknownModel {
+            robot_id: robot_id.to_string(),
+        })?;
+        let validator = self
+            .models
+            .get(model)
+            .and_then(|commands| commands.get(name))
+            .ok_or_else(|| CatalogError::UnknownCommand {
+                model: model.to_string(),
+                name: name.to_string(),
+            })?;
+
+        let errors: Vec<(String, String)> = validator
+            .iter_errors(params)
+            .map(|e| (e.instance_path.to_string(), e.to_string()))
+            .collect();
+        if !errors.is_empty() {
+            return Err(CatalogError::InvalidParams {
+                name: name.to_string(),
+                errors,
+            });
+        }
+        Ok(model)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+
+    use serde_json::Map;
+
+    use super::*;
+
+    /// 테스트마다 다른 카탈로그 파일
+    fn write_catalog(name: &str, contents: &str) -> PathBuf {
+        let path = std::env::temp_dir().join(format!("gateway-catalog-{}-{name}.json", std::process::id()));
+        fs::write(&path, contents).unwrap();
+        path
+    }
+
+    fn load(name: &str, file: Value) -> CommandCatalog {
+        let path = write_catalog(name, &file.to_string());
+        CommandCatalog::load(path.to_str().unwrap()).unwrap()
+    }
+
+    fn catalog(name: &str) -> CommandCatalog {
+        load(
+            name,
+            json!({
+                "robots": { "robot-01": "acme-x1" },
+                "models": {
+                    "acme-x1": {
+                        "set_led": {
+                            "schema": {
+                                "type": "object",
+                                "required": ["color"],
+                                "properties": { "color": { "type": "string" } }
+                            }
+                        },
+                        "beep": {}
+                    }
+                }
+            }),
+        )
+    }
+
+    fn params(value: Value) -> Map<String, Value> {
+        value.as_object().cloned().unwrap()
+    }
+
+    #[test]
+    fn robot_without_model_is_unknown_model() {
+        let catalog = catalog("unknown-model");
+        let err = catalog.validate("robot-99", "set_led", &json!({ "color": "red" })).unwrap_err();
+        assert!(matches!(err, CatalogError::UnknownModel { ref robot_id } if robot_id == "robot-99"));
+        assert_eq!(err.details(), json!({ "robot_id": "robot-99" }));
+    }
+
+    #[test]
+    fn default_model_covers_unlisted_robots() {
+        let catalog = load(
+            "default-model",
+            json!({ "default_model": "acme-x1", "models": { "acme-x1": { "beep": {} } } }),
+        );
+        assert_eq!(catalog.model_for("robot-99"), Some("acme-x1"));
+        assert_eq!(catalog.validate("robot-99", "beep", &json!({})).unwrap(), "acme-x1");
+    }
+
+    #[test]
+    fn undeclared_command_is_unknown_command() {
+        let catalog = catalog("unknown-command");
+        let err = catalog.validate("robot-01", "self_destruct", &json!({})).unwrap_err();
+        assert!(matches!(err, CatalogError::UnknownCommand { .. }));
+        assert_eq!(err.details(), json!({ "model": "acme-x1", "name": "self_destruct" }));
+    }
+
+    #[test]
+    fn schema_violation_reports_path_and_message() {
+        let catalog = catalog("schema-violation");
+        assert_eq!(catalog.validate("robot-01", "set_led", &json!({ "color": "red" })).unwrap(), "acme-x1");
+        // schema가 생략된 명령은 아무 object나 허용한다.
+        assert!(catalog.validate("robot-01", "beep", &json!({ "volume": 3 })).is_ok());
+
+        let err = catalog.validate("robot-01", "set_led", &json!({ "color": 7 })).unwrap_err();
+        let CatalogError::InvalidParams { ref name, ref errors } = err else {
+            panic!("expected InvalidParams, got {err:?}");
+        };
+        assert_eq!(name, "set_led");
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].0, "/color");
+
+        let details = err.details();
+        assert_eq!(details["name"], "set_led");
+        assert_eq!(details["errors"][0]["path"], "/color");
+        assert!(details["errors"][0]["message"].as_str().is_some_and(|m| !m.is_empty()));
+
+        let err = catalog.validate("robot-01", "set_led", &json!({})).unwrap_err();
+        assert!(matches!(err, CatalogError::InvalidParams { .. }));
+    }
+
+    #[test]
+    fn vendor_payload_gets_model_from_catalog() {
+        let catalog = catalog("vendor-model");
+        let mut payload = ControlPayload::Vendor {
+            model: "spoofed".to_string(),
+            name: "set_led".to_string(),
+            params: params(json!({ "color": "red" })),
+        };
+        catalog.check_payload("robot-01", &mut payload).unwrap();
+        let ControlPayload::Vendor { model, .. } = payload else { unreachable!() };
+        assert_eq!(model, "acme-x1");
+
+        let mut payload = ControlPayload::Vendor {
+            model: String::new(),
+            name: "undeclared".to_string(),
+            params: Map::new(),
+        };
+        assert!(matches!(
+            catalog.check_payload("robot-01", &mut payload),
+            Err(CatalogError::UnknownCommand { .. })
+        ));
+    }
+
+    #[test]
+    fn custom_payload_is_checked_only_when_declared() {
+        let catalog = catalog("custom");
+
+        // 선언된 이름이면 같은 schema로 검사한다.
+        let mut declared = ControlPayload::Custom {
+            name: "set_led".to_string(),
+            params: params(json!({ "color": 7 })),
+        };
+        assert!(matches!(
+            catalog.check_payload("robot-01", &mut declared),
+            Err(CatalogError::InvalidParams { .. })
+        ));
+
+        // 선언되지 않은 이름이나 model이 없는 robot은 그대로 통과한다.
+        let mut undeclared = ControlPayload::Custom {
+            name: "dance".to_string(),
+            params: params(json!({ "tempo": "fast" })),
+        };
+        assert!(catalog.check_payload("robot-01", &mut undeclared).is_ok());
+        let mut declared = ControlPayload::Custom {
+            name: "set_led".to_string(),
+            params: params(json!({ "color": 7 })),
+        };
+        assert!(catalog.check_payload("robot-99", &mut declared).is_ok());
+    }
+
+    #[test]
+    fn load_fails_on_missing_or_invalid_file() {
+        let missing = std::env::temp_dir().join(format!("gateway-catalog-{}-missing.json", std::process::id()));
+        let err = CommandCatalog::load(missing.to_str().unwrap()).err().unwrap();
+        assert!(err.to_string().contains("failed to read command catalog"));
+
+        let path = write_catalog("not-json", "{ models: ");
+        let err = CommandCatalog::load(path.to_str().unwrap()).err().unwrap();
+        assert!(err.to_string().contains("failed to parse command catalog"));
+
+        let path = write_catalog(
+            "bad-schema",
+            &json!({ "models": { "acme-x1": { "set_led": { "schema": { "type": 12 } } } } }).to_string(),
+        );
+        let err = CommandCatalog::load(path.to_str().unwrap()).err().unwrap();
+        assert!(err.to_string().contains("invalid schema for acme-x1/set_led"));
+    }
+}
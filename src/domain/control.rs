@@ -1,7 +1,10 @@
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 
+use crate::domain::signal::CommandType;
+
 /// WebSocket <-> Gateway control 메시지
+#[allow(dead_code)]
 #[derive(Debug, Serialize, Deserialize)]
 #[serde(tag = "type")]
 pub enum WsControlMessage {
@@ -40,44 +43,259 @@ pub enum WsControlMessage {
         robot_id: String,
         payload: PathFollowPayload,
     },
+
+    #[serde(rename = "velocity")]
+    Velocity {
+        robot_id: String,
+        payload: VelocityPayload,
+    },
+
+    #[serde(rename = "navigate_to")]
+    NavigateTo {
+        robot_id: String,
+        payload: NavigateToPayload,
+    },
+
+    #[serde(rename = "undock")]
+    Undock {
+        robot_id: String,
+        payload: EmptyPayload,
+    },
+
+    #[serde(rename = "pause")]
+    Pause {
+        robot_id: String,
+        payload: EmptyPayload,
+    },
+
+    #[serde(rename = "resume")]
+    Resume {
+        robot_id: String,
+        payload: EmptyPayload,
+    },
+
+    #[serde(rename = "cancel")]
+    Cancel {
+        robot_id: String,
+        payload: EmptyPayload,
+    },
+
+    #[serde(rename = "custom")]
+    Custom {
+        robot_id: String,
+        payload: CustomPayload,
+    },
+
+    #[serde(rename = "vendor")]
+    Vendor {
+        robot_id: String,
+        payload: CustomPayload,
+    },
+
+    #[serde(rename = "reset_estop")]
+    ResetEstop {
+        robot_id: String,
+        payload: EmptyPayload,
+    },
+
+    #[serde(rename = "heartbeat")]
+    Heartbeat {
+        robot_id: String,
+        payload: EmptyPayload,
+    },
+
+    #[serde(rename = "request_control")]
+    RequestControl {
+        robot_id: String,
+        payload: EmptyPayload,
+    },
+
+    #[serde(rename = "release_control")]
+    ReleaseControl {
+        robot_id: String,
+        payload: EmptyPayload,
+    },
+
+    #[serde(rename = "handover_control")]
+    HandoverControl {
+        robot_id: String,
+        payload: HandoverPayload,
+    },
 }
+#[allow(dead_code)]
 #[derive(Debug, Serialize, Deserialize)]
 pub struct MovePayload {
-    pub direction: String, // "forward" | "backward" | "left" | "right"
+    pub direction: Direction,
     pub speed: f32,
+    /// 지정하면 gateway가 이 시간 뒤 STOP을 보낸다. (step-jog 등 한정된 이동)
+    #[serde(default)]
+    pub duration_ms: Option<u64>,
+}
+
+/// move 명령이 허용하는 방향
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum Direction {
+    Forward,
+    Backward,
+    Left,
+    Right,
+}
+
+impl Direction {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Direction::Forward => "forward",
+            Direction::Backward => "backward",
+            Direction::Left => "left",
+            Direction::Right => "right",
+        }
+    }
+}
+
+impl std::str::FromStr for Direction {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "forward" => Ok(Direction::Forward),
+            "backward" => Ok(Direction::Backward),
+            "left" => Ok(Direction::Left),
+            "right" => Ok(Direction::Right),
+            other => Err(anyhow::anyhow!(
+                "invalid direction: {other} (expected forward | backward | left | right)"
+            )),
+        }
+    }
 }
 
+#[allow(dead_code)]
 #[derive(Debug, Serialize, Deserialize, Default)]
 pub struct EmptyPayload {}
 
+#[allow(dead_code)]
 #[derive(Debug, Serialize, Deserialize)]
 pub struct SetSpeedPayload {
     pub speed: f32,
 }
 
+#[allow(dead_code)]
 #[derive(Debug, Serialize, Deserialize)]
 pub struct PathFollowPayload {
     pub path_id: String,
 }
 
+/// 연속 속도 명령 (joystick/gamepad). 생략한 성분은 0으로 본다.
+#[allow(dead_code)]
+#[derive(Debug, Serialize, Deserialize)]
+pub struct VelocityPayload {
+    #[serde(default)]
+    pub linear_x: f64, // m/s
+    #[serde(default)]
+    pub linear_y: f64, // m/s
+    #[serde(default)]
+    pub angular_z: f64, // rad/s
+}
+
+/// 목표 pose로 이동 (지도 UI의 click-to-go)
+#[allow(dead_code)]
+#[derive(Debug, Serialize, Deserialize)]
+pub struct NavigateToPayload {
+    pub x: f64, // m
+    pub y: f64, // m
+    #[serde(default)]
+    pub theta: f64, // rad
+    #[serde(default = "default_frame_id")]
+    pub frame_id: String,
+    #[serde(default)]
+    pub tolerance: f64, // m, 0이면 robot 기본값
+}
+
+pub fn default_frame_id() -> String {
+    "map".to_string()
+}
+
+/// vendor별 명령 (custom, vendor 공용).
+/// custom은 그대로 전달하고, vendor는 카탈로그의 schema로 params를 검증한 뒤 전달한다.
+#[allow(dead_code)]
+#[derive(Debug, Serialize, Deserialize)]
+pub struct CustomPayload {
+    pub name: String,
+    #[serde(default)]
+    pub params: serde_json::Map<String, Value>,
+}
+
+#[allow(dead_code)]
+#[derive(Debug, Serialize, Deserialize)]
+pub struct HandoverPayload {
+    pub session_id: u64, // lease를 넘겨받을 control 세션
+}
+
 #[derive(Debug, Serialize, Deserialize)]
 #[serde(tag = "type")]
 pub enum WsControlResponse {
     #[serde(rename = "control_ack")]
     Ack {
         robot_id: String,
+
+        // 클라이언트가 보낸 ControlRequest.request_id를 그대로 돌려준다.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        request_id: Option<String>,
+
         message: String,
     },
 
     #[serde(rename = "control_error")]
     Error {
         robot_id: String,
+
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        request_id: Option<String>,
+
+        // 클라이언트가 분기 처리할 수 있는 오류 종류
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        code: Option<ControlErrorCode>,
+
         message: String,
+
+        // code별 추가 정보 (예: schema 검증 실패 위치)
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        details: Option<Value>,
     },
 }
 
+/// control_error의 code 값
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum ControlErrorCode {
+    /// seq가 이전에 받은 값보다 크지 않음
+    OutOfOrder,
+    /// timestamp_ms 기준으로 허용 시간보다 늦게 도착
+    Expired,
+    /// payload 형식/값이 잘못됨 (필수 필드 누락, 잘못된 direction, NaN/음수 속도 등)
+    InvalidPayload,
+    /// 속도가 상한을 넘음 (speed_policy = "reject")
+    SpeedLimitExceeded,
+    /// e_stop 이후 reset_estop 전까지 motion 명령 불가
+    EstopLatched,
+    /// 이 클라이언트에게 허용되지 않은 명령
+    Forbidden,
+    /// gRPC outbound 큐가 가득 참 (backend가 따라오지 못함), 잠시 후 재시도
+    QueueFull,
+    /// 명령 종류/세션/robot별 전송 한도 초과 ([control.rate_limit])
+    RateLimited,
+    /// 카탈로그에 없는 vendor 명령 (details에 model/name)
+    UnknownCommand,
+    /// 다른 control 세션이 lease를 보유 중 (exclusive_lease)
+    LeaseHeld,
+    /// lease가 필요함 (보유하지 않았거나 만료됨, request_control 먼저)
+    LeaseRequired,
+    /// 이 gateway는 lease를 쓰지 않음 (exclusive_lease = false)
+    LeaseDisabled,
+}
+
 /// 클라이언트 → Gateway 제어 요청 형태 (raw JSON)
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, Clone, Copy, PartialEq, Eq, Hash)]
 #[serde(rename_all = "snake_case")]
 pub enum ControlRequestType {
     Move,
@@ -86,6 +304,109 @@ pub enum ControlRequestType {
     SetSpeed,
     Dock,
     PathFollow,
+    /// 연속 속도 명령 (linear_x, linear_y, angular_z)
+    Velocity,
+    /// 목표 pose로 자율 주행 (x, y, theta, frame_id, tolerance)
+    NavigateTo,
+    Undock,
+    /// 진행 중인 작업 일시 정지 / 재개 / 취소
+    Pause,
+    Resume,
+    Cancel,
+    /// vendor별 명령 (name + 임의의 JSON params)
+    Custom,
+    /// 카탈로그에 선언된 vendor 명령 (name + schema 검증된 params)
+    Vendor,
+    /// e_stop latch 해제 (권한 있는 role만)
+    ResetEstop,
+    /// 클라이언트 생존 신호 (robot으로 전달하지 않음)
+    Heartbeat,
+    /// 제어권(lease) 요청/반납/이양 (gateway에서 처리)
+    RequestControl,
+    ReleaseControl,
+    HandoverControl,
+}
+
+impl ControlRequestType {
+    /// heartbeat watchdog을 갱신하는 요청인지 (heartbeat 또는 새 move/velocity)
+    pub fn refreshes_heartbeat(&self) -> bool {
+        matches!(
+            self,
+            ControlRequestType::Heartbeat | ControlRequestType::Move | ControlRequestType::Velocity
+        )
+    }
+
+    /// lease 관련 요청인지
+    pub fn is_lease_request(&self) -> bool {
+        matches!(
+            self,
+            ControlRequestType::RequestControl
+                | ControlRequestType::ReleaseControl
+                | ControlRequestType::HandoverControl
+        )
+    }
+
+    /// robot으로 전달되는 명령 종류 (gateway가 처리하는 요청은 None)
+    pub fn command_type(&self) -> Option<CommandType> {
+        let cmd = match self {
+            ControlRequestType::Move => CommandType::Move,
+            ControlRequestType::Stop => CommandType::Stop,
+            ControlRequestType::EStop => CommandType::EmergencyStop,
+            ControlRequestType::SetSpeed => CommandType::SetSpeed,
+            ControlRequestType::Dock => CommandType::Dock,
+            ControlRequestType::PathFollow => CommandType::PathFollow,
+            ControlRequestType::Velocity => CommandType::Velocity,
+            ControlRequestType::NavigateTo => CommandType::NavigateTo,
+            ControlRequestType::Undock => CommandType::Undock,
+            ControlRequestType::Pause => CommandType::Pause,
+            ControlRequestType::Resume => CommandType::Resume,
+            ControlRequestType::Cancel => CommandType::Cancel,
+            ControlRequestType::Custom => CommandType::Custom,
+            ControlRequestType::Vendor => CommandType::Vendor,
+            ControlRequestType::ResetEstop => CommandType::ResetEstop,
+            ControlRequestType::Heartbeat
+            | ControlRequestType::RequestControl
+            | ControlRequestType::ReleaseControl
+            | ControlRequestType::HandoverControl => return None,
+        };
+        Some(cmd)
+    }
+
+    /// robot을 움직이게 하는 명령인지 (`CommandType::is_motion`과 같은 기준)
+    pub fn is_motion(&self) -> bool {
+        self.command_type().is_some_and(|cmd| cmd.is_motion())
+    }
+
+    /// lease 없이도 보낼 수 있는 명령인지 (e_stop은 누구나 보낼 수 있어야 한다)
+    pub fn is_lease_exempt(&self) -> bool {
+        matches!(self, ControlRequestType::EStop)
+    }
+
+    /// 이전 시간 제한 move의 STOP 예약을 대체(취소)하는 명령인지 (motion 명령과 stop/e_stop)
+    pub fn cancels_timed_stop(&self) -> bool {
+        self.is_motion() || matches!(self, ControlRequestType::Stop | ControlRequestType::EStop)
+    }
+
+    /// rate limit을 적용하지 않는 명령인지 (정지 명령은 절대 막지 않는다)
+    pub fn is_rate_limit_exempt(&self) -> bool {
+        matches!(self, ControlRequestType::EStop | ControlRequestType::Stop)
+    }
+
+    /// 권한 정책과 무관하게 허용되는 명령인지 (control 채널에 붙은 누구나 e_stop은 보낼 수 있다)
+    pub fn is_policy_exempt(&self) -> bool {
+        matches!(self, ControlRequestType::EStop)
+    }
+}
+
+/// control_ack을 언제 보낼지
+#[derive(Debug, Deserialize, Default, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum AckMode {
+    /// gateway가 signaling 스트림에 넣은 시점에 ack (기본값)
+    #[default]
+    Gateway,
+    /// robot이 CommandResult를 돌려준 시점에 ack, 제한 시간 내 응답이 없으면 control_error
+    Robot,
 }
 
 #[derive(Debug, Deserialize)]
@@ -95,4 +416,18 @@ pub struct ControlRequest {
 
     #[serde(default)]
     pub payload: Value,
+
+    #[serde(default)]
+    pub ack: AckMode,
+
+    /// 클라이언트가 붙이는 임의의 id. 이 요청에 대한 모든 응답에 그대로 실린다.
+    #[serde(default)]
+    pub request_id: Option<String>,
+    /// 클라이언트가 요청을 만든 시각 (unix epoch ms)
+    #[serde(default)]
+    pub timestamp_ms: Option<u64>,
+
+    /// 세션 안에서 단조 증가하는 seq
+    #[serde(default)]
+    pub seq: Option<u64>,
 }
@@ -1,4 +1,5 @@
 use anyhow::{anyhow, Result};
+use serde_json::{Map, Number, Value};
 
 use crate::domain::signal::{
     WsSignalMessage, WsIceCandidate, WsSessionDescription, CommandType, ControlPayload,
@@ -8,6 +9,7 @@ use crate::domain::signal::{
 use crate::protocol::robot::signaling::{
     signal_message,
     ClientAnswer,
+    CommandResult,
     ControlCommand as GrpcControlCommand,
     IceCandidate,
     RobotOffer,
@@ -18,6 +20,10 @@ use crate::protocol::robot::signaling::{
     MovePayload,
     SetSpeedPayload,
     PathFollowPayload,
+    VelocityPayload,
+    NavigateToPayload,
+    CustomPayload,
+    VendorCommandPayload,
 };
 
 impl From<CommandType> for GrpcCommandType {
@@ -29,6 +35,15 @@ impl From<CommandType> for GrpcCommandType {
             CommandType::SetSpeed => GrpcCommandType::SetSpeed,
             CommandType::Dock => GrpcCommandType::Dock,
             CommandType::PathFollow => GrpcCommandType::PathFollow,
+            CommandType::ResetEstop => GrpcCommandType::ResetEstop,
+            CommandType::Velocity => GrpcCommandType::Velocity,
+            CommandType::NavigateTo => GrpcCommandType::NavigateTo,
+            CommandType::Pause => GrpcCommandType::Pause,
+            CommandType::Resume => GrpcCommandType::Resume,
+            CommandType::Cancel => GrpcCommandType::Cancel,
+            CommandType::Undock => GrpcCommandType::Undock,
+            CommandType::Custom => GrpcCommandType::Custom,
+            CommandType::Vendor => GrpcCommandType::Vendor,
         }
     }
 }
@@ -44,6 +59,15 @@ impl TryFrom<GrpcCommandType> for CommandType {
             GrpcCommandType::SetSpeed => Ok(CommandType::SetSpeed),
             GrpcCommandType::Dock => Ok(CommandType::Dock),
             GrpcCommandType::PathFollow => Ok(CommandType::PathFollow),
+            GrpcCommandType::ResetEstop => Ok(CommandType::ResetEstop),
+            GrpcCommandType::Velocity => Ok(CommandType::Velocity),
+            GrpcCommandType::NavigateTo => Ok(CommandType::NavigateTo),
+            GrpcCommandType::Pause => Ok(CommandType::Pause),
+            GrpcCommandType::Resume => Ok(CommandType::Resume),
+            GrpcCommandType::Cancel => Ok(CommandType::Cancel),
+            GrpcCommandType::Undock => Ok(CommandType::Undock),
+            GrpcCommandType::Custom => Ok(CommandType::Custom),
+            GrpcCommandType::Vendor => Ok(CommandType::Vendor),
             GrpcCommandType::CommandUnknown => Err(anyhow!("unknown control command type")),
         }
     }
@@ -105,12 +129,18 @@ impl TryFrom<WsSignalMessage> for SignalMessage {
             WsSignalMessage::ControlCommand {
                 robot_id,
                 command,
+                command_id,
+                sequence,
                 payload,
             } => {
                 let grpc_payload = match payload {
-                    Some(ControlPayload::Move { direction, speed }) => {
+                    Some(ControlPayload::Move { direction, speed, duration_ms }) => {
                         Some(crate::protocol::robot::signaling::control_command::Payload::Move(
-                            MovePayload { direction, speed },
+                            MovePayload {
+                                direction,
+                                speed,
+                                duration_ms: duration_ms.unwrap_or_default(),
+                            },
                         ))
                     }
                     Some(ControlPayload::SetSpeed { speed }) => {
@@ -123,6 +153,33 @@ impl TryFrom<WsSignalMessage> for SignalMessage {
                             PathFollowPayload { path_id },
                         ))
                     }
+                    Some(ControlPayload::Velocity { linear_x, linear_y, angular_z }) => {
+                        Some(crate::protocol::robot::signaling::control_command::Payload::Velocity(
+                            VelocityPayload { linear_x, linear_y, angular_z },
+                        ))
+                    }
+                    Some(ControlPayload::NavigateTo { x, y, theta, frame_id, tolerance }) => {
+                        Some(crate::protocol::robot::signaling::control_command::Payload::NavigateTo(
+                            NavigateToPayload { x, y, theta, frame_id, tolerance },
+                        ))
+                    }
+                    Some(ControlPayload::Custom { name, params }) => {
+                        Some(crate::protocol::robot::signaling::control_command::Payload::Custom(
+                            CustomPayload {
+                                name,
+                                params: Some(json_to_struct(params)),
+                            },
+                        ))
+                    }
+                    Some(ControlPayload::Vendor { model, name, params }) => {
+                        Some(crate::protocol::robot::signaling::control_command::Payload::Vendor(
+                            VendorCommandPayload {
+                                model,
+                                name,
+                                params: Some(json_to_struct(params)),
+                            },
+                        ))
+                    }
                     None => None,
                 };
 
@@ -132,10 +189,26 @@ impl TryFrom<WsSignalMessage> for SignalMessage {
                     robot_id,
                     payload: Some(signal_message::Payload::ControlCommand(GrpcControlCommand {
                         command: grpc_command,
+                        command_id: command_id.unwrap_or_default(),
+                        sequence: sequence.unwrap_or_default(),
                         payload: grpc_payload,
                     })),
                 })
             }
+
+            WsSignalMessage::CommandResult {
+                robot_id,
+                command_id,
+                success,
+                message,
+            } => Ok(SignalMessage {
+                robot_id,
+                payload: Some(signal_message::Payload::CommandResult(CommandResult {
+                    command_id,
+                    success,
+                    message,
+                })),
+            }),
         }
     }
 }
@@ -205,14 +278,15 @@ impl TryFrom<SignalMessage> for WsSignalMessage {
             /* ---------- Control ---------- */
 
             Some(signal_message::Payload::ControlCommand(cmd)) => {
-                let grpc_cmd = GrpcCommandType::from_i32(cmd.command)
-                    .ok_or_else(|| anyhow!("unknown control command type value: {}", cmd.command))?;
+                let grpc_cmd = GrpcCommandType::try_from(cmd.command)
+                    .map_err(|_| anyhow!("unknown control command type value: {}", cmd.command))?;
 
                 let payload = match cmd.payload {
                     Some(crate::protocol::robot::signaling::control_command::Payload::Move(m)) => {
                         Some(ControlPayload::Move {
                             direction: m.direction,
                             speed: m.speed,
+                            duration_ms: (m.duration_ms != 0).then_some(m.duration_ms),
                         })
                     }
                     Some(crate::protocol::robot::signaling::control_command::Payload::SetSpeed(s)) => {
@@ -223,17 +297,217 @@ impl TryFrom<SignalMessage> for WsSignalMessage {
                             path_id: p.path_id,
                         })
                     }
+                    Some(crate::protocol::robot::signaling::control_command::Payload::Velocity(v)) => {
+                        Some(ControlPayload::Velocity {
+                            linear_x: v.linear_x,
+                            linear_y: v.linear_y,
+                            angular_z: v.angular_z,
+                        })
+                    }
+                    Some(crate::protocol::robot::signaling::control_command::Payload::NavigateTo(n)) => {
+                        Some(ControlPayload::NavigateTo {
+                            x: n.x,
+                            y: n.y,
+                            theta: n.theta,
+                            frame_id: n.frame_id,
+                            tolerance: n.tolerance,
+                        })
+                    }
+                    Some(crate::protocol::robot::signaling::control_command::Payload::Custom(c)) => {
+                        Some(ControlPayload::Custom {
+                            name: c.name,
+                            params: c.params.map(struct_to_json).unwrap_or_default(),
+                        })
+                    }
+                    Some(crate::protocol::robot::signaling::control_command::Payload::Vendor(v)) => {
+                        Some(ControlPayload::Vendor {
+                            model: v.model,
+                            name: v.name,
+                            params: v.params.map(struct_to_json).unwrap_or_default(),
+                        })
+                    }
                     None => None,
                 };
 
                 Ok(WsSignalMessage::ControlCommand {
                     robot_id,
                     command: grpc_cmd.try_into()?,
+                    command_id: (!cmd.command_id.is_empty()).then_some(cmd.command_id),
+                    sequence: (cmd.sequence != 0).then_some(cmd.sequence),
                     payload,
                 })
             }
 
+            Some(signal_message::Payload::CommandResult(r)) => {
+                Ok(WsSignalMessage::CommandResult {
+                    robot_id,
+                    command_id: r.command_id,
+                    success: r.success,
+                    message: r.message,
+                })
+            }
+
             None => Err(anyhow!("empty SignalMessage payload")),
         }
     }
 }
+
+/* ============================
+ * JSON <-> google.protobuf.Struct (custom 명령 params)
+ * ============================ */
+
+fn json_to_struct(map: Map<String, Value>) -> prost_types::Struct {
+    prost_types::Struct {
+        fields: map.into_iter().map(|(k, v)| (k, json_to_value(v))).collect(),
+    }
+}
+
+fn json_to_value(value: Value) -> prost_types::Value {
+    use prost_types::value::Kind;
+
+    let kind = match value {
+        Value::Null => Kind::NullValue(prost_types::NullValue::NullValue as i32),
+        Value::Bool(b) => Kind::BoolValue(b),
+        // Struct의 숫자는 double 뿐이다. (2^53을 넘는 정수는 정밀도가 떨어진다)
+        Value::Number(n) => Kind::NumberValue(n.as_f64().unwrap_or_default()),
+        Value::String(s) => Kind::StringValue(s),
+        Value::Array(items) => Kind::ListValue(prost_types::ListValue {
+            values: items.into_iter().map(json_to_value).collect(),
+        }),
+        Value::Object(map) => Kind::StructValue(json_to_struct(map)),
+    };
+    prost_types::Value { kind: Some(kind) }
+}
+
+fn struct_to_json(s: prost_types::Struct) -> Map<String, Value> {
+    s.fields.into_iter().map(|(k, v)| (k, value_to_json(v))).collect()
+}
+
+fn value_to_json(value: prost_types::Value) -> Value {
+    use prost_types::value::Kind;
+
+    match value.kind {
+        None | Some(Kind::NullValue(_)) => Value::Null,
+        Some(Kind::BoolValue(b)) => Value::Bool(b),
+        Some(Kind::NumberValue(n)) => {
+            // 정수로 표현 가능한 값은 정수로 돌려준다. (NaN/inf는 JSON에 없으므로 null)
+            if n.fract() == 0.0 && n.abs() < 9_007_199_254_740_992.0 {
+                Value::Number(Number::from(n as i64))
+            } else {
+                Number::from_f64(n).map(Value::Number).unwrap_or(Value::Null)
+            }
+        }
+        Some(Kind::StringValue(s)) => Value::String(s),
+        Some(Kind::ListValue(list)) => Value::Array(list.values.into_iter().map(value_to_json).collect()),
+        Some(Kind::StructValue(s)) => Value::Object(struct_to_json(s)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    /// WsSignalMessage -> SignalMessage -> WsSignalMessage 후 JSON이 같은지 확인한다.
+    fn assert_round_trip(ws: WsSignalMessage) -> SignalMessage {
+        let before = serde_json::to_value(&ws).unwrap();
+        let grpc = SignalMessage::try_from(ws).unwrap();
+        let after = serde_json::to_value(WsSignalMessage::try_from(grpc.clone()).unwrap()).unwrap();
+        assert_eq!(before, after);
+        grpc
+    }
+
+    fn command(command: CommandType, payload: Option<ControlPayload>) -> WsSignalMessage {
+        WsSignalMessage::ControlCommand {
+            robot_id: "robot-01".to_string(),
+            command,
+            command_id: Some("7-1".to_string()),
+            sequence: Some(42),
+            payload,
+        }
+    }
+
+    #[test]
+    fn payloadless_task_commands_round_trip() {
+        for (ws, grpc) in [
+            (CommandType::Pause, GrpcCommandType::Pause),
+            (CommandType::Resume, GrpcCommandType::Resume),
+            (CommandType::Cancel, GrpcCommandType::Cancel),
+            (CommandType::Undock, GrpcCommandType::Undock),
+        ] {
+            let msg = assert_round_trip(command(ws, None));
+            let Some(signal_message::Payload::ControlCommand(cmd)) = msg.payload else {
+                panic!("expected control command");
+            };
+            assert_eq!(cmd.command, grpc as i32);
+            assert!(cmd.payload.is_none());
+        }
+    }
+
+    #[test]
+    fn move_duration_round_trips() {
+        let timed = ControlPayload::Move {
+            direction: "forward".to_string(),
+            speed: 0.5,
+            duration_ms: Some(1500),
+        };
+        assert_round_trip(command(CommandType::Move, Some(timed)));
+
+        let untimed = ControlPayload::Move {
+            direction: "left".to_string(),
+            speed: 1.0,
+            duration_ms: None,
+        };
+        assert_round_trip(command(CommandType::Move, Some(untimed)));
+    }
+
+    #[test]
+    fn velocity_round_trips() {
+        let payload = ControlPayload::Velocity {
+            linear_x: 0.4,
+            linear_y: -0.1,
+            angular_z: 1.25,
+        };
+        assert_round_trip(command(CommandType::Velocity, Some(payload)));
+    }
+
+    #[test]
+    fn navigate_to_round_trips() {
+        let payload = ControlPayload::NavigateTo {
+            x: 12.5,
+            y: -3.25,
+            theta: std::f64::consts::FRAC_PI_2,
+            frame_id: "map".to_string(),
+            tolerance: 0.2,
+        };
+        assert_round_trip(command(CommandType::NavigateTo, Some(payload)));
+    }
+
+    #[test]
+    fn custom_struct_params_round_trip() {
+        let params = json!({
+            "color": "red",
+            "blink_hz": 2.5,
+            "count": 3,
+            "enabled": true,
+            "note": null,
+            "pattern": [1, 0, 1],
+            "nested": { "level": 2, "tags": ["a", "b"] },
+        });
+        let payload = ControlPayload::Custom {
+            name: "set_led".to_string(),
+            params: params.as_object().unwrap().clone(),
+        };
+        assert_round_trip(command(CommandType::Custom, Some(payload)));
+    }
+
+    #[test]
+    fn vendor_round_trips() {
+        let payload = ControlPayload::Vendor {
+            model: "acme-x1".to_string(),
+            name: "play_sound".to_string(),
+            params: json!({ "sound_id": "chime", "volume": 80 }).as_object().unwrap().clone(),
+        };
+        assert_round_trip(command(CommandType::Vendor, Some(payload)));
+    }
+}
@@ -1,3 +1,5 @@
 pub mod control;
 pub mod signal;
-pub mod convert;
\ No newline at end of file
+pub mod convert;
+pub mod validation;
+pub mod catalog;
\ No newline at end of file
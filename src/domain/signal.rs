@@ -1,4 +1,5 @@
 use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
 
 /* ============================
  * WebSocket <-> Gateway Signal
@@ -51,9 +52,25 @@ pub enum WsSignalMessage {
         robot_id: String,
         command: CommandType,
 
+        // robot이 CommandResult로 돌려주는 id
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        command_id: Option<String>,
+
+        // 클라이언트가 보낸 단조 증가 seq
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        sequence: Option<u64>,
+
         #[serde(flatten)]
         payload: Option<ControlPayload>,
     },
+
+    #[serde(rename = "command_result")]
+    CommandResult {
+        robot_id: String,
+        command_id: String,
+        success: bool,
+        message: String,
+    },
 }
 
 /* ============================
@@ -69,6 +86,46 @@ pub enum CommandType {
     SetSpeed,
     Dock,
     PathFollow,
+    ResetEstop,
+    Velocity,
+    NavigateTo,
+    Pause,
+    Resume,
+    Cancel,
+    Undock,
+    Custom,
+    Vendor,
+}
+
+impl CommandType {
+    /// 정지 명령인지 (outbound 큐의 priority lane으로 간다)
+    pub fn is_stop(&self) -> bool {
+        matches!(self, CommandType::Stop | CommandType::EmergencyStop)
+    }
+
+    /// robot을 움직이게 하는 명령인지 (e_stop latch 중에는 거절된다)
+    /// custom/vendor 명령은 내용을 알 수 없으므로 움직이는 명령으로 본다.
+    pub fn is_motion(&self) -> bool {
+        matches!(
+            self,
+            CommandType::Move
+                | CommandType::SetSpeed
+                | CommandType::Dock
+                | CommandType::PathFollow
+                | CommandType::Velocity
+                | CommandType::NavigateTo
+                | CommandType::Undock
+                | CommandType::Resume
+                | CommandType::Custom
+                | CommandType::Vendor
+        )
+    }
+
+    /// 새 값이 오면 이전 값이 의미 없어지는 연속 motion 명령인지
+    /// (outbound 큐에서 latest-wins로 덮어쓰거나 overflow 시 버릴 수 있다)
+    pub fn is_continuous(&self) -> bool {
+        matches!(self, CommandType::Move | CommandType::SetSpeed | CommandType::Velocity)
+    }
 }
 
 /* ============================
@@ -82,6 +139,10 @@ pub enum ControlPayload {
     Move {
         direction: String,
         speed: f64,
+
+        // 시간 제한 move: 이 시간이 지나면 gateway가 STOP을 보낸다.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        duration_ms: Option<u64>,
     },
 
     #[serde(rename = "set_speed")]
@@ -93,6 +154,39 @@ pub enum ControlPayload {
     PathFollow {
         path_id: String,
     },
+
+    #[serde(rename = "velocity")]
+    Velocity {
+        linear_x: f64,
+        linear_y: f64,
+        angular_z: f64,
+    },
+
+    #[serde(rename = "navigate_to")]
+    NavigateTo {
+        x: f64,
+        y: f64,
+        theta: f64,
+        frame_id: String,
+        tolerance: f64,
+    },
+
+    #[serde(rename = "custom")]
+    Custom {
+        name: String,
+        #[serde(default)]
+        params: Map<String, Value>,
+    },
+
+    // model은 gateway가 카탈로그에서 채운다.
+    #[serde(rename = "vendor")]
+    Vendor {
+        #[serde(default)]
+        model: String,
+        name: String,
+        #[serde(default)]
+        params: Map<String, Value>,
+    },
 }
 
 /* ============================
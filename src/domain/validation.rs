@@ -0,0 +1,289 @@
+use std::fmt;
+
+use crate::config::configs::{SpeedLimits, SpeedPolicy};
+use crate::domain::control::ControlErrorCode;
+use crate::domain::signal::ControlPayload;
+
+/// 제어 명령 검증 실패 (control_error의 code와 message로 그대로 내려간다)
+#[derive(Debug)]
+pub struct ValidationError {
+    pub code: ControlErrorCode,
+    pub message: String,
+}
+
+impl ValidationError {
+    fn invalid(message: impl Into<String>) -> Self {
+        Self {
+            code: ControlErrorCode::InvalidPayload,
+            message: message.into(),
+        }
+    }
+}
+
+impl fmt::Display for ValidationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.message)
+    }
+}
+
+impl std::error::Error for ValidationError {}
+
+/// robot으로 보내기 전에 payload 값을 검사한다.
+/// 선속도는 `cap`, 회전 속도는 `limits.max_angular_speed`, move 시간은 `limits.max_move_duration_ms`를
+/// 넘으면 `limits.speed_policy`에 따라 잘라내거나 거절하며, 잘라냈으면 Ok(true)를 돌려준다.
+pub fn validate_control_payload(
+    payload: &mut ControlPayload,
+    cap: f64,
+    limits: &SpeedLimits,
+) -> Result<bool, ValidationError> {
+    let policy = limits.speed_policy;
+    match payload {
+        ControlPayload::Move { duration_ms: Some(0), .. } => {
+            Err(ValidationError::invalid("duration_ms must be greater than 0"))
+        }
+        ControlPayload::Move { speed, duration_ms, .. } => {
+            let clamped = limit_speed(speed, cap, policy)?;
+            let shortened = match duration_ms {
+                Some(ms) => limit_duration(ms, limits.max_move_duration_ms, policy)?,
+                None => false,
+            };
+            Ok(clamped || shortened)
+        }
+        ControlPayload::SetSpeed { speed } => limit_speed(speed, cap, policy),
+        ControlPayload::Velocity { linear_x, linear_y, angular_z } => {
+            let linear = limit_velocity(linear_x, linear_y, angular_z, cap, policy)?;
+            let angular = limit_angular(angular_z, limits.max_angular_speed, policy)?;
+            Ok(linear || angular)
+        }
+        ControlPayload::NavigateTo { x, y, theta, frame_id, tolerance } => {
+            if !x.is_finite() || !y.is_finite() || !theta.is_finite() {
+                return Err(ValidationError::invalid("navigate_to pose must be finite numbers"));
+            }
+            if !tolerance.is_finite() || *tolerance < 0.0 {
+                return Err(ValidationError::invalid(format!(
+                    "tolerance must be a non-negative number, got {tolerance}"
+                )));
+            }
+            if frame_id.trim().is_empty() {
+                return Err(ValidationError::invalid("frame_id must not be empty"));
+            }
+            Ok(false)
+        }
+        ControlPayload::Custom { name, .. } | ControlPayload::Vendor { name, .. } => {
+            if name.trim().is_empty() {
+                return Err(ValidationError::invalid("command name must not be empty"));
+            }
+            Ok(false)
+        }
+        ControlPayload::PathFollow { path_id } => {
+            if path_id.trim().is_empty() {
+                return Err(ValidationError::invalid("path_id must not be empty"));
+            }
+            Ok(false)
+        }
+    }
+}
+
+/// 선속도는 (linear_x, linear_y) 크기로 상한을 적용하고, clamp면 방향을 유지한 채 줄인다.
+fn limit_velocity(
+    linear_x: &mut f64,
+    linear_y: &mut f64,
+    angular_z: &mut f64,
+    cap: f64,
+    policy: SpeedPolicy,
+) -> Result<bool, ValidationError> {
+    if !linear_x.is_finite() || !linear_y.is_finite() || !angular_z.is_finite() {
+        return Err(ValidationError::invalid("velocity components must be finite numbers"));
+    }
+
+    let mut magnitude = linear_x.hypot(*linear_y);
+    let original = magnitude;
+    let clamped = limit_speed(&mut magnitude, cap, policy)?;
+    if clamped && original > 0.0 {
+        let scale = magnitude / original;
+        *linear_x *= scale;
+        *linear_y *= scale;
+    }
+    Ok(clamped)
+}
+
+/// 시간 제한 move가 정해진 시간 안에 끝나도록 duration_ms에 상한을 적용한다.
+fn limit_duration(duration_ms: &mut u64, max: u64, policy: SpeedPolicy) -> Result<bool, ValidationError> {
+    if *duration_ms <= max {
+        return Ok(false);
+    }
+
+    match policy {
+        SpeedPolicy::Clamp => {
+            *duration_ms = max;
+            Ok(true)
+        }
+        SpeedPolicy::Reject => Err(ValidationError::invalid(format!(
+            "duration_ms {duration_ms} exceeds limit {max}"
+        ))),
+    }
+}
+
+/// 회전 속도는 부호(방향)를 유지한 채 |angular_z|에 상한을 적용한다.
+fn limit_angular(angular_z: &mut f64, cap: f64, policy: SpeedPolicy) -> Result<bool, ValidationError> {
+    if angular_z.abs() <= cap {
+        return Ok(false);
+    }
+
+    match policy {
+        SpeedPolicy::Clamp => {
+            *angular_z = cap.copysign(*angular_z);
+            Ok(true)
+        }
+        SpeedPolicy::Reject => Err(ValidationError {
+            code: ControlErrorCode::SpeedLimitExceeded,
+            message: format!("angular_z {angular_z} exceeds limit {cap}"),
+        }),
+    }
+}
+
+fn limit_speed(speed: &mut f64, cap: f64, policy: SpeedPolicy) -> Result<bool, ValidationError> {
+    if !speed.is_finite() {
+        return Err(ValidationError::invalid("speed must be a finite number"));
+    }
+    if *speed < 0.0 {
+        return Err(ValidationError::invalid(format!("speed must not be negative, got {speed}")));
+    }
+    if *speed <= cap {
+        return Ok(false);
+    }
+
+    match policy {
+        SpeedPolicy::Clamp => {
+            *speed = cap;
+            Ok(true)
+        }
+        SpeedPolicy::Reject => Err(ValidationError {
+            code: ControlErrorCode::SpeedLimitExceeded,
+            message: format!("speed {speed} exceeds limit {cap}"),
+        }),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn limits(policy: SpeedPolicy) -> SpeedLimits {
+        SpeedLimits {
+            max_speed: 1.0,
+            max_angular_speed: 0.5,
+            max_move_duration_ms: 3_000,
+            speed_policy: policy,
+            ..SpeedLimits::default()
+        }
+    }
+
+    fn set_speed(speed: f64) -> ControlPayload {
+        ControlPayload::SetSpeed { speed }
+    }
+
+    fn timed_move(speed: f64, duration_ms: Option<u64>) -> ControlPayload {
+        ControlPayload::Move {
+            direction: "forward".to_string(),
+            speed,
+            duration_ms,
+        }
+    }
+
+    fn velocity(linear_x: f64, linear_y: f64, angular_z: f64) -> ControlPayload {
+        ControlPayload::Velocity { linear_x, linear_y, angular_z }
+    }
+
+    #[test]
+    fn speed_over_cap_is_clamped_or_rejected_by_policy() {
+        // (speed, policy, 기대 결과: Ok(clamped) 또는 Err(code), 검증 후 speed)
+        let cases = [
+            (0.5, SpeedPolicy::Clamp, Ok(false), 0.5),
+            (1.0, SpeedPolicy::Clamp, Ok(false), 1.0),
+            (1.5, SpeedPolicy::Clamp, Ok(true), 1.0),
+            (0.5, SpeedPolicy::Reject, Ok(false), 0.5),
+            (1.5, SpeedPolicy::Reject, Err(ControlErrorCode::SpeedLimitExceeded), 1.5),
+        ];
+
+        for (speed, policy, expected, after) in cases {
+            let mut payload = set_speed(speed);
+            let result = validate_control_payload(&mut payload, 1.0, &limits(policy)).map_err(|e| e.code);
+            assert_eq!(result, expected, "speed={speed} policy={policy:?}");
+            let ControlPayload::SetSpeed { speed } = payload else { unreachable!() };
+            assert_eq!(speed, after);
+        }
+    }
+
+    #[test]
+    fn non_finite_or_negative_speed_is_rejected_under_both_policies() {
+        for policy in [SpeedPolicy::Clamp, SpeedPolicy::Reject] {
+            for speed in [f64::NAN, f64::INFINITY, f64::NEG_INFINITY, -0.1] {
+                let err = validate_control_payload(&mut set_speed(speed), 1.0, &limits(policy)).unwrap_err();
+                assert_eq!(err.code, ControlErrorCode::InvalidPayload, "speed={speed} policy={policy:?}");
+
+                let err = validate_control_payload(&mut timed_move(speed, None), 1.0, &limits(policy)).unwrap_err();
+                assert_eq!(err.code, ControlErrorCode::InvalidPayload, "move speed={speed} policy={policy:?}");
+            }
+
+            for component in [f64::NAN, f64::INFINITY] {
+                for mut payload in [
+                    velocity(component, 0.0, 0.0),
+                    velocity(0.0, component, 0.0),
+                    velocity(0.0, 0.0, component),
+                ] {
+                    let err = validate_control_payload(&mut payload, 1.0, &limits(policy)).unwrap_err();
+                    assert_eq!(err.code, ControlErrorCode::InvalidPayload, "{payload:?}");
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn velocity_clamp_keeps_direction_and_angular_sign() {
+        let mut payload = velocity(3.0, 4.0, -2.0);
+        assert!(validate_control_payload(&mut payload, 1.0, &limits(SpeedPolicy::Clamp)).unwrap());
+
+        let ControlPayload::Velocity { linear_x, linear_y, angular_z } = payload else { unreachable!() };
+        assert!((linear_x - 0.6).abs() < 1e-9);
+        assert!((linear_y - 0.8).abs() < 1e-9);
+        assert_eq!(angular_z, -0.5);
+
+        let mut payload = velocity(0.0, 0.0, 2.0);
+        assert!(validate_control_payload(&mut payload, 1.0, &limits(SpeedPolicy::Clamp)).unwrap());
+        let ControlPayload::Velocity { angular_z, .. } = payload else { unreachable!() };
+        assert_eq!(angular_z, 0.5);
+    }
+
+    #[test]
+    fn velocity_over_angular_cap_is_rejected_under_reject() {
+        for mut payload in [velocity(0.0, 0.0, 0.6), velocity(0.0, 0.0, -0.6), velocity(0.6, 0.8, 0.0)] {
+            let err = validate_control_payload(&mut payload, 0.9, &limits(SpeedPolicy::Reject)).unwrap_err();
+            assert_eq!(err.code, ControlErrorCode::SpeedLimitExceeded, "{payload:?}");
+        }
+
+        let mut payload = velocity(0.3, 0.4, -0.5);
+        assert!(!validate_control_payload(&mut payload, 1.0, &limits(SpeedPolicy::Reject)).unwrap());
+    }
+
+    #[test]
+    fn move_duration_is_capped_by_policy() {
+        let mut payload = timed_move(0.5, Some(5_000));
+        assert!(validate_control_payload(&mut payload, 1.0, &limits(SpeedPolicy::Clamp)).unwrap());
+        let ControlPayload::Move { duration_ms, .. } = payload else { unreachable!() };
+        assert_eq!(duration_ms, Some(3_000));
+
+        let mut payload = timed_move(0.5, Some(5_000));
+        let err = validate_control_payload(&mut payload, 1.0, &limits(SpeedPolicy::Reject)).unwrap_err();
+        assert_eq!(err.code, ControlErrorCode::InvalidPayload);
+
+        for policy in [SpeedPolicy::Clamp, SpeedPolicy::Reject] {
+            let mut payload = timed_move(0.5, Some(3_000));
+            assert!(!validate_control_payload(&mut payload, 1.0, &limits(policy)).unwrap());
+
+            let mut payload = timed_move(0.5, Some(0));
+            let err = validate_control_payload(&mut payload, 1.0, &limits(policy)).unwrap_err();
+            assert_eq!(err.code, ControlErrorCode::InvalidPayload);
+        }
+    }
+}
@@ -3,6 +3,7 @@ mod config;
 mod app;
 mod session;
 mod domain;
+mod auth;
 
 #[tokio::main]
 async fn main() -> anyhow::Result<()> {
@@ -11,11 +12,11 @@ async fn main() -> anyhow::Result<()> {
     env_logger::Builder::from_env(env_logger::Env::default().default_filter_or("info")).init();
     log::info!("server starting");
 
-    let settings = config::configs::load_settings();
+    let settings = config::configs::load_settings()?;
     let grpc_endpoint = format!("http://{}:{}", settings.grpc_client.to_ip, settings.grpc_client.to_port);
     let ws_bind_addr = format!("{}:{}", settings.websocket_server.self_ip, settings.websocket_server.self_port);
     
-    let app = app::gateway_app::GatewayApp::new(grpc_endpoint).await?;
+    let app = app::gateway_app::GatewayApp::new(grpc_endpoint, settings).await?;
     app.run(ws_bind_addr.as_str()).await?;
 
     Ok(())
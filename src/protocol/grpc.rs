@@ -1,81 +1,261 @@
 use futures_util::StreamExt;
 use anyhow::anyhow;
 use tonic::transport::{Channel, Endpoint};
+use std::collections::HashMap;
 use std::sync::Arc;
-use tokio::sync::{mpsc, Mutex};
-use tokio_stream::wrappers::UnboundedReceiverStream;
-use log::{info, error, debug};
+use tokio::sync::Mutex;
+use tokio::sync::mpsc::error::TrySendError;
+use log::{info, warn, error, debug};
 
+use crate::app::metrics::GatewayMetrics;
+use crate::config::configs::{GRPCConfig, SignalStreamMode};
+use crate::protocol::outbound_queue::{OutboundQueue, QueueDepth, QueueError};
 use crate::session::manager::SharedSessions;
 
 use crate::protocol::robot::signaling::{
     robot_signal_service_client::RobotSignalServiceClient,
+    signal_message,
     SignalMessage,
 };
 
-pub struct GrpcClient {
-    signal: RobotSignalServiceClient<Channel>,
+// shared 모드에서 모든 robot이 함께 쓰는 스트림의 key
+const SHARED_STREAM_KEY: &str = "";
+
+/// outbound sender (Gateway -> grpc-robot-api).
+/// EMERGENCY_STOP / STOP은 priority lane으로 보내 ICE/SDP 등 이미 쌓인 트래픽보다 먼저 나가게 한다.
+/// 나머지는 bounded lane이며, 가득 차면 `QueueError::Full` (같은 robot의 가장 오래된 motion은 버림).
+#[derive(Clone)]
+pub struct SignalSender {
+    queue: Arc<OutboundQueue>,
+}
+
+impl SignalSender {
+    /// 큐에서 밀려난 motion 명령이 있으면 그 command_id들을 돌려준다.
+    pub fn send(&self, msg: SignalMessage) -> Result<Vec<String>, QueueError> {
+        self.queue.push(msg)
+    }
+}
+
+/// 큐가 닫히고 비워질 때까지 메시지를 꺼내는 outbound 스트림
+fn outbound_stream(queue: Arc<OutboundQueue>) -> impl futures_util::Stream<Item = SignalMessage> {
+    futures_util::stream::unfold(queue, |queue| async move {
+        let msg = queue.pop().await?;
+        Some((msg, queue))
+    })
+}
+
+/// 같은 robot을 보고 있는 모든 viewer에게 fan-out.
+/// viewer 큐가 가득 차면 따라오지 못하는 viewer로 보고 끊는다.
+async fn fan_out(sessions: &SharedSessions, metrics: &GatewayMetrics, robot_id: &str, msg: SignalMessage) {
+    let mut slow_viewers = Vec::new();
+    for (session_id, ws_tx) in sessions.read().await.get_ws_senders(robot_id) {
+        if let Err(TrySendError::Full(_)) = ws_tx.try_send(msg.clone()) {
+            slow_viewers.push(session_id);
+        }
+    }
+    if !slow_viewers.is_empty() {
+        let mut guard = sessions.write().await;
+        for session_id in slow_viewers {
+            warn!("[grpc] viewer queue full, disconnecting slow viewer robot_id={robot_id} session_id={session_id}");
+            guard.remove(robot_id, session_id);
+            metrics.record_slow_viewer_disconnected();
+        }
+    }
+}
 
+/// bi-di signaling 스트림 하나의 상태.
+/// shared 모드면 1개, per_robot 모드면 robot_id마다 1개씩 생긴다.
+#[derive(Default)]
+struct SignalStream {
     // lazy-init된 outbound sender (Gateway -> grpc-robot-api), 재연결 가능
-    signal_tx: Arc<Mutex<Option<mpsc::UnboundedSender<SignalMessage>>>>,
+    tx: Mutex<Option<SignalSender>>,
 
     // init 경쟁 방지
     init_lock: Mutex<()>,
+
+    // 이 스트림을 사용 중인 WS 세션 수 (0이 되면 스트림을 닫는다)
+    active_sessions: Mutex<usize>,
+}
+
+impl SignalStream {
+    /// gRPC bi-di signaling 스트림을 명시적으로 닫는다.
+    /// outbound 큐를 닫으면 남은 메시지를 보낸 뒤 tonic outbound 스트림이 종료되어 서버도 정리된다.
+    async fn close(&self) {
+        let mut guard = self.tx.lock().await;
+        if let Some(sender) = guard.take() {
+            debug!("[grpc] closing signal stream (close outbound queue)");
+            sender.queue.close();
+        }
+    }
+}
+
+/// stream key -> 스트림 상태와 세션 refcount.
+/// key는 shared 모드면 SHARED_STREAM_KEY, per_robot 모드면 robot_id.
+struct SignalStreams {
+    mode: SignalStreamMode,
+    streams: Mutex<HashMap<String, Arc<SignalStream>>>,
+}
+
+impl SignalStreams {
+    fn new(mode: SignalStreamMode) -> Self {
+        Self {
+            mode,
+            streams: Mutex::new(HashMap::new()),
+        }
+    }
+
+    fn key<'a>(&self, robot_id: &'a str) -> &'a str {
+        match self.mode {
+            SignalStreamMode::Shared => SHARED_STREAM_KEY,
+            SignalStreamMode::PerRobot => robot_id,
+        }
+    }
+
+    /// 조회만 한다. 스트림은 acquire에서만 만들어지므로, release된 뒤 늦게 도착한 송신이
+    /// 주인 없는(refcount 0) 항목을 다시 만들지 않는다.
+    async fn get(&self, robot_id: &str) -> Option<Arc<SignalStream>> {
+        self.streams.lock().await.get(self.key(robot_id)).cloned()
+    }
+
+    async fn acquire(&self, robot_id: &str) -> usize {
+        // map lock을 쥔 채로 증감해서 release의 스트림 제거와 경쟁하지 않게 한다.
+        let mut streams = self.streams.lock().await;
+        let key = self.key(robot_id);
+        let stream = streams.entry(key.to_string()).or_default();
+
+        let mut count = stream.active_sessions.lock().await;
+        *count += 1;
+        debug!("[grpc] signal stream acquired (key={key:?}, active_sessions={})", *count);
+        *count
+    }
+
+    async fn release(&self, robot_id: &str) -> usize {
+        let mut streams = self.streams.lock().await;
+        let key = self.key(robot_id);
+        let Some(stream) = streams.get(key).cloned() else {
+            return 0;
+        };
+
+        let mut count = stream.active_sessions.lock().await;
+        *count = count.saturating_sub(1);
+        debug!("[grpc] signal stream released (key={key:?}, active_sessions={})", *count);
+
+        if *count == 0 {
+            stream.close().await;
+            streams.remove(key);
+        }
+        *count
+    }
+
+    /// 열려 있는 스트림 (key, 상태) 목록
+    async fn snapshot(&self) -> Vec<(String, Arc<SignalStream>)> {
+        self.streams
+            .lock()
+            .await
+            .iter()
+            .map(|(key, stream)| (key.clone(), stream.clone()))
+            .collect()
+    }
+}
+
+pub struct GrpcClient {
+    signal: RobotSignalServiceClient<Channel>,
+
+    // stream마다 bounded outbound lane의 크기
+    queue_capacity: usize,
+    coalesce_motion: bool,
+    metrics: Arc<GatewayMetrics>,
+
+    // stream key -> 스트림 상태 (shared 모드면 1개, per_robot 모드면 robot_id마다)
+    streams: SignalStreams,
+
+    // 아직 다 나가지 않은 outbound 큐 (스트림이 정리된 뒤에도 종료 시 flush를 기다리기 위해 보관)
+    outbound_queues: Mutex<Vec<Arc<OutboundQueue>>>,
 }
 
 impl GrpcClient {
-    pub async fn connect(addr: String) -> anyhow::Result<Self> {
+    pub async fn connect(
+        addr: String,
+        config: &GRPCConfig,
+        metrics: Arc<GatewayMetrics>,
+    ) -> anyhow::Result<Self> {
         let channel = Endpoint::from_shared(addr)?.connect().await?;
+        let mode = config.stream_mode;
+        info!(
+            "[grpc] connected (stream_mode={:?}, outbound_queue_capacity={}, coalesce_motion={})",
+            mode, config.outbound_queue_capacity, config.coalesce_motion
+        );
 
         Ok(Self {
             signal: RobotSignalServiceClient::new(channel),
-            signal_tx: Arc::new(Mutex::new(None)),
-            init_lock: Mutex::new(()),
+            queue_capacity: config.outbound_queue_capacity,
+            coalesce_motion: config.coalesce_motion,
+            metrics,
+            streams: SignalStreams::new(mode),
+            outbound_queues: Mutex::new(Vec::new()),
         })
     }
 
     pub async fn ensure_signal_stream(
         &self,
         sessions: SharedSessions,
+        robot_id: &str,
         initial: Option<SignalMessage>,
     ) -> anyhow::Result<()> {
-        if let Some(sender) = self.signal_tx.lock().await.clone() {
+        let key = self.streams.key(robot_id).to_string();
+        let stream = self
+            .streams
+            .get(robot_id)
+            .await
+            .ok_or_else(|| anyhow!("signal stream not acquired (key={key:?}, call acquire_signal_stream first)"))?;
+
+        if let Some(sender) = stream.tx.lock().await.clone() {
             if let Some(ref msg) = initial {
                 sender
                     .send(msg.clone())
                     .map_err(|e| anyhow!("failed to send initial signal: {e}"))?;
             }
-            debug!("[grpc] ensure_signal_stream: already initialized");
+            debug!("[grpc] ensure_signal_stream: already initialized (key={key:?})");
             return Ok(());
         }
 
-        let _g = self.init_lock.lock().await;
-        if let Some(sender) = self.signal_tx.lock().await.clone() {
+        let _g = stream.init_lock.lock().await;
+        if let Some(sender) = stream.tx.lock().await.clone() {
             if let Some(ref msg) = initial {
                 sender
                     .send(msg.clone())
                     .map_err(|e| anyhow!("failed to send initial signal: {e}"))?;
             }
-            debug!("[grpc] ensure_signal_stream: already initialized (after lock)");
+            debug!("[grpc] ensure_signal_stream: already initialized after lock (key={key:?})");
             return Ok(());
         }
 
-        info!("[grpc] ensure_signal_stream: opening bi-di stream...");
+        info!("[grpc] ensure_signal_stream: opening bi-di stream (key={key:?})...");
 
-        // Gateway -> grpc-robot-api outbound
-        let (tx, rx) = mpsc::unbounded_channel::<SignalMessage>();
+        // Gateway -> grpc-robot-api outbound (priority lane + bounded lane)
+        let queue = Arc::new(OutboundQueue::new(
+            self.queue_capacity,
+            self.coalesce_motion,
+            self.metrics.clone(),
+        ));
+        {
+            let mut queues = self.outbound_queues.lock().await;
+            queues.retain(|q| !q.is_finished());
+            queues.push(queue.clone());
+        }
+        let tx = SignalSender { queue: queue.clone() };
         if let Some(msg) = initial {
             tx.send(msg)
                 .map_err(|e| anyhow!("failed to send initial signal before open: {e}"))?;
         }
-        let outbound = UnboundedReceiverStream::new(rx);
+        let outbound = outbound_stream(queue);
 
         // 실제 RPC 호출은 여기서 발생 (lazy-init)
         let mut client = self.signal.clone();
         let response = match client.open_signal_stream(outbound).await {
             Ok(resp) => resp,
             Err(e) => {
-                error!("[grpc] failed to open signal stream: {:?}", e);
+                error!("[grpc] failed to open signal stream (key={key:?}): {:?}", e);
                 return Err(e.into());
             }
         };
@@ -84,13 +264,14 @@ impl GrpcClient {
 
         // sender 저장
         {
-            let mut guard = self.signal_tx.lock().await;
+            let mut guard = stream.tx.lock().await;
             *guard = Some(tx.clone());
         }
-        info!("[grpc] ensure_signal_stream: stream opened and sender stored");
+        info!("[grpc] ensure_signal_stream: stream opened and sender stored (key={key:?})");
 
-        // inbound receiver spawn (1회)
-        let signal_tx = self.signal_tx.clone();
+        // 스트림마다 inbound receiver spawn (1회)
+        let stream_state = stream.clone();
+        let metrics = self.metrics.clone();
         tokio::spawn(async move {
             let mut inbound = Box::pin(inbound);
             let mut last_err: Option<tonic::Status> = None;
@@ -98,16 +279,22 @@ impl GrpcClient {
             while let Some(item) = inbound.next().await {
                 match item {
                     Ok(msg) => {
-                        debug!("[grpc] inbound msg for robot_id={}", msg.robot_id);
+                        debug!("[grpc] inbound msg for robot_id={} (key={key:?})", msg.robot_id);
                         let robot_id = msg.robot_id.clone();
 
-                        let guard = sessions.read().await;
-                        if let Some(ws_tx) = guard.get_ws_sender(&robot_id) {
-                            let _ = ws_tx.send(msg);
+                        // 명령 결과는 viewer가 아니라 명령을 보낸 control 세션으로 돌려보낸다.
+                        if let Some(signal_message::Payload::CommandResult(result)) = msg.payload {
+                            let command_id = result.command_id.clone();
+                            if !sessions.write().await.complete_command(result) {
+                                debug!("[grpc] no pending command for result command_id={command_id} robot_id={robot_id}");
+                            }
+                            continue;
                         }
+
+                        fan_out(&sessions, &metrics, &robot_id, msg).await;
                     }
                     Err(e) => {
-                        error!("[grpc] inbound stream error: {:?}", e);
+                        error!("[grpc] inbound stream error (key={key:?}): {:?}", e);
                         last_err = Some(e);
                         break;
                     }
@@ -117,43 +304,231 @@ impl GrpcClient {
             match inbound.trailers().await {
                 Ok(Some(md)) => {
                     error!(
-                        "[grpc] signaling stream closed with trailers: {:?} last_err={:?}",
+                        "[grpc] signaling stream closed with trailers (key={key:?}): {:?} last_err={:?}",
                         md, last_err
                     );
                 }
                 Ok(None) => {
-                    error!("[grpc] signaling stream closed cleanly last_err={:?}", last_err);
+                    error!("[grpc] signaling stream closed cleanly (key={key:?}) last_err={:?}", last_err);
                 }
                 Err(status) => {
                     error!(
-                        "[grpc] signaling stream trailers error: {:?} last_err={:?}",
+                        "[grpc] signaling stream trailers error (key={key:?}): {:?} last_err={:?}",
                         status, last_err
                     );
                 }
             }
-            // 연결이 종료되면 sender를 비워 재연결을 허용
-            let mut guard = signal_tx.lock().await;
-            *guard = None;
+            // 연결이 종료되면 이 스트림의 sender만 비워 재연결을 허용 (다른 robot 스트림은 영향 없음)
+            stream_state.close().await;
         });
 
         Ok(())
     }
 
-    pub async fn signal_sender(&self) -> anyhow::Result<mpsc::UnboundedSender<SignalMessage>> {
-        self.signal_tx
+    pub async fn signal_sender(&self, robot_id: &str) -> anyhow::Result<SignalSender> {
+        let stream = self
+            .streams
+            .get(robot_id)
+            .await
+            .ok_or_else(|| anyhow!("signal stream not acquired for {robot_id} (released or never acquired)"))?;
+        stream
+            .tx
             .lock()
             .await
             .clone()
-            .ok_or_else(|| anyhow::anyhow!("signal stream not initialized (call ensure_signal_stream first)"))
+            .ok_or_else(|| anyhow!("signal stream not initialized (call ensure_signal_stream first)"))
     }
 
-    /// gRPC bi-di signaling 스트림을 명시적으로 닫는다.
-    /// sender를 drop 하면 tonic outbound 스트림이 종료되어 서버도 정리된다.
-    pub async fn close_signal_stream(&self) {
-        let mut guard = self.signal_tx.lock().await;
-        if guard.is_some() {
-            debug!("[grpc] closing signal stream (drop sender)");
+    /// WS 세션이 signaling 스트림을 사용하기 시작할 때 호출한다.
+    /// 반드시 `release_signal_stream`과 짝을 맞춰야 하며, 해당 스트림을 사용 중인 세션 수를 반환한다.
+    pub async fn acquire_signal_stream(&self, robot_id: &str) -> usize {
+        self.streams.acquire(robot_id).await
+    }
+
+    /// WS 세션 종료 시 호출한다. 스트림의 마지막 세션이 빠질 때만 스트림을 닫아
+    /// 다른 robot/viewer 세션의 signaling이 끊기지 않도록 한다.
+    pub async fn release_signal_stream(&self, robot_id: &str) -> usize {
+        self.streams.release(robot_id).await
+    }
+
+    /// 모든 outbound 큐를 닫고, 이미 쌓인 메시지(dead-man STOP 등)가 tonic 스트림으로
+    /// 모두 넘어가 스트림이 끝날 때까지 기다린다. 종료 시 timeout과 함께 호출한다.
+    pub async fn flush_outbound(&self) {
+        let queues = std::mem::take(&mut *self.outbound_queues.lock().await);
+        for queue in &queues {
+            queue.close();
+        }
+        futures_util::future::join_all(queues.iter().map(|queue| queue.finished())).await;
+    }
+
+    /// stream key별 outbound 큐 깊이 (열려 있는 스트림만)
+    pub async fn queue_depths(&self) -> Vec<(String, QueueDepth)> {
+        let mut depths = Vec::new();
+        for (key, stream) in self.streams.snapshot().await {
+            if let Some(sender) = stream.tx.lock().await.as_ref() {
+                depths.push((key, sender.queue.depth()));
+            }
         }
-        *guard = None;
+        depths
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+    use crate::protocol::robot::signaling::ScreenRequest;
+    use crate::session::manager::SessionManager;
+
+    /// ensure_signal_stream 대신 outbound 큐만 붙여 둔다. (tonic Channel 없이 refcount만 검사)
+    async fn attach_queue(streams: &SignalStreams, robot_id: &str) -> Arc<OutboundQueue> {
+        let queue = Arc::new(OutboundQueue::new(8, false, Arc::new(GatewayMetrics::default())));
+        let stream = streams.get(robot_id).await.expect("acquire before attach_queue");
+        *stream.tx.lock().await = Some(SignalSender { queue: queue.clone() });
+        queue
+    }
+
+    /// 세션마다 별도 task에서 acquire하고 가장 큰 refcount를 돌려준다.
+    async fn acquire_concurrently(streams: &Arc<SignalStreams>, robots: &[&'static str]) -> usize {
+        let tasks: Vec<_> = robots
+            .iter()
+            .map(|&robot_id| {
+                let streams = streams.clone();
+                tokio::spawn(async move { streams.acquire(robot_id).await })
+            })
+            .collect();
+        let mut max = 0;
+        for task in tasks {
+            max = max.max(task.await.unwrap());
+        }
+        max
+    }
+
+    fn is_open(queue: &OutboundQueue) -> bool {
+        let probe = SignalMessage {
+            robot_id: "probe".to_string(),
+            payload: Some(signal_message::Payload::ScreenRequest(ScreenRequest {})),
+        };
+        queue.push(probe) != Err(QueueError::Closed)
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+    async fn shared_stream_closes_on_last_release_only() {
+        let streams = Arc::new(SignalStreams::new(SignalStreamMode::Shared));
+        let robots = ["robot-01", "robot-02", "robot-01", "robot-03"];
+        assert_eq!(acquire_concurrently(&streams, &robots).await, 4);
+        let queue = attach_queue(&streams, "robot-01").await;
+
+        for (released, robot_id) in robots.iter().enumerate().take(3) {
+            assert_eq!(streams.release(robot_id).await, 3 - released);
+            assert!(is_open(&queue), "closed after releasing {robot_id}");
+        }
+        assert_eq!(streams.release("robot-03").await, 0);
+        assert!(!is_open(&queue));
+        assert!(streams.snapshot().await.is_empty());
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+    async fn per_robot_streams_are_counted_independently() {
+        let streams = Arc::new(SignalStreams::new(SignalStreamMode::PerRobot));
+        acquire_concurrently(&streams, &["robot-01", "robot-01", "robot-02"]).await;
+        let robot_01 = attach_queue(&streams, "robot-01").await;
+        let robot_02 = attach_queue(&streams, "robot-02").await;
+
+        // robot-02의 마지막 세션이 빠져도 robot-01 스트림은 유지된다.
+        assert_eq!(streams.release("robot-02").await, 0);
+        assert!(!is_open(&robot_02));
+        assert!(is_open(&robot_01));
+
+        assert_eq!(streams.release("robot-01").await, 1);
+        assert!(is_open(&robot_01));
+        assert_eq!(streams.release("robot-01").await, 0);
+        assert!(!is_open(&robot_01));
+
+        // 이미 정리된 스트림을 다시 release해도 음수가 되지 않는다.
+        assert_eq!(streams.release("robot-01").await, 0);
+    }
+
+    /// 연결하지 않는 Channel로 만든 client (스트림 조회/송신 경로만 검사)
+    fn lazy_client(mode: SignalStreamMode) -> GrpcClient {
+        let channel = Endpoint::from_static("http://127.0.0.1:1").connect_lazy();
+        GrpcClient {
+            signal: RobotSignalServiceClient::new(channel),
+            queue_capacity: 8,
+            coalesce_motion: false,
+            metrics: Arc::new(GatewayMetrics::default()),
+            streams: SignalStreams::new(mode),
+            outbound_queues: Mutex::new(Vec::new()),
+        }
+    }
+
+    #[tokio::test]
+    async fn per_robot_send_after_release_does_not_recreate_the_stream() {
+        let client = lazy_client(SignalStreamMode::PerRobot);
+        let sessions: SharedSessions = Arc::new(tokio::sync::RwLock::new(SessionManager::new(
+            Duration::from_secs(30),
+            1,
+            None,
+        )));
+
+        // acquire 전에는 송신도 스트림 열기도 실패하고 항목이 생기지 않는다.
+        assert!(client.signal_sender("robot-01").await.is_err());
+        assert!(client.ensure_signal_stream(sessions.clone(), "robot-01", None).await.is_err());
+        assert!(client.streams.snapshot().await.is_empty());
+
+        client.acquire_signal_stream("robot-01").await;
+        client.acquire_signal_stream("robot-02").await;
+        let queue = attach_queue(&client.streams, "robot-01").await;
+        attach_queue(&client.streams, "robot-02").await;
+        assert!(client.signal_sender("robot-01").await.is_ok());
+
+        assert_eq!(client.release_signal_stream("robot-01").await, 0);
+        assert!(!is_open(&queue));
+
+        // release 뒤 늦게 도착한 송신은 실패하고, robot-01 항목이 다시 생기지 않는다.
+        assert!(client.signal_sender("robot-01").await.is_err());
+        assert!(client.ensure_signal_stream(sessions, "robot-01", None).await.is_err());
+        let keys: Vec<_> = client.streams.snapshot().await.into_iter().map(|(key, _)| key).collect();
+        assert_eq!(keys, vec!["robot-02".to_string()]);
+        assert!(client.signal_sender("robot-02").await.is_ok());
+    }
+
+    #[tokio::test]
+    async fn fan_out_disconnects_only_the_slow_viewer() {
+        let sessions: SharedSessions = Arc::new(tokio::sync::RwLock::new(SessionManager::new(
+            Duration::from_secs(30),
+            1,
+            None,
+        )));
+        let metrics = GatewayMetrics::default();
+        let (fast, mut fast_rx) = sessions.write().await.insert("robot-01".to_string());
+        let (slow, _slow_rx) = sessions.write().await.insert("robot-01".to_string());
+        let (other, mut other_rx) = sessions.write().await.insert("robot-02".to_string());
+
+        let screen_request = |robot_id: &str| SignalMessage {
+            robot_id: robot_id.to_string(),
+            payload: Some(signal_message::Payload::ScreenRequest(ScreenRequest {})),
+        };
+        fan_out(&sessions, &metrics, "robot-01", screen_request("robot-01")).await;
+        assert!(fast_rx.try_recv().is_ok());
+
+        // 비워진 viewer는 계속 받고, 큐가 가득 찬 viewer만 끊긴다.
+        fan_out(&sessions, &metrics, "robot-01", screen_request("robot-01")).await;
+        assert!(fast_rx.try_recv().is_ok());
+        let viewers: Vec<_> = sessions
+            .read()
+            .await
+            .get_ws_senders("robot-01")
+            .into_iter()
+            .map(|(id, _)| id)
+            .collect();
+        assert_eq!(viewers, [fast]);
+        assert_ne!(fast, slow);
+        assert_eq!(metrics.snapshot().slow_viewers_disconnected, 1);
+
+        // 다른 robot의 viewer는 영향 없음
+        fan_out(&sessions, &metrics, "robot-02", screen_request("robot-02")).await;
+        assert!(other_rx.try_recv().is_ok());
+        assert_eq!(sessions.read().await.get_ws_senders("robot-02")[0].0, other);
     }
 }
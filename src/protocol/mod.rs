@@ -1,5 +1,6 @@
 pub mod websocket;
 pub mod grpc;
+pub mod outbound_queue;
 
 pub mod robot {
     pub mod signaling {
@@ -0,0 +1,534 @@
+use std::collections::VecDeque;
+use std::fmt;
+use std::sync::{Arc, Mutex};
+use tokio::sync::Notify;
+
+use crate::app::metrics::GatewayMetrics;
+use crate::domain::signal::CommandType;
+use crate::protocol::robot::signaling::{signal_message, CommandType as GrpcCommandType, SignalMessage};
+
+/// outbound 메시지 분류 (분류마다 overflow 정책이 다르다)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum MessageClass {
+    /// EMERGENCY_STOP / STOP: 절대 버리지 않고 항상 먼저 나간다.
+    Priority,
+    /// MOVE / SET_SPEED / VELOCITY: 큐가 가득 차면 같은 robot의 가장 오래된 motion 명령부터 버린다.
+    Motion,
+    /// 그 외 control 명령 (DOCK, PATH_FOLLOW 등): 버리지 않고, 자리가 없으면 거절한다.
+    Command,
+    /// WebRTC signaling (SDP/ICE 등): 자리가 없으면 거절한다.
+    Signaling,
+}
+
+fn classify(msg: &SignalMessage) -> MessageClass {
+    let Some(signal_message::Payload::ControlCommand(cmd)) = &msg.payload else {
+        return MessageClass::Signaling;
+    };
+
+    // 분류 기준은 domain의 CommandType과 같다 (e_stop latch가 막는 motion 목록과 어긋나지 않도록)
+    let Some(command) = GrpcCommandType::try_from(cmd.command)
+        .ok()
+        .and_then(|grpc| CommandType::try_from(grpc).ok())
+    else {
+        return MessageClass::Command;
+    };
+    if command.is_stop() {
+        MessageClass::Priority
+    } else if command.is_motion() && command.is_continuous() {
+        MessageClass::Motion
+    } else {
+        MessageClass::Command
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum QueueError {
+    /// 큐가 가득 차서 거절됨 (스트림은 살아 있음)
+    Full,
+    /// 스트림이 닫힘 (재연결 필요)
+    Closed,
+}
+
+impl fmt::Display for QueueError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            QueueError::Full => f.write_str("outbound queue is full"),
+            QueueError::Closed => f.write_str("outbound queue is closed"),
+        }
+    }
+}
+
+impl std::error::Error for QueueError {}
+
+fn motion_key(msg: &SignalMessage) -> Option<(&str, i32)> {
+    match &msg.payload {
+        Some(signal_message::Payload::ControlCommand(cmd)) => Some((msg.robot_id.as_str(), cmd.command)),
+        _ => None,
+    }
+}
+
+fn command_id(msg: SignalMessage) -> Option<String> {
+    match msg.payload {
+        Some(signal_message::Payload::ControlCommand(cmd)) if !cmd.command_id.is_empty() => Some(cmd.command_id),
+        _ => None,
+    }
+}
+
+/// lane별 대기 중인 메시지 수
+#[derive(Debug, Clone, Copy, Default)]
+pub struct QueueDepth {
+    pub priority: usize,
+    pub control: usize,
+    pub signaling: usize,
+}
+
+#[derive(Default)]
+struct Lanes {
+    priority: VecDeque<SignalMessage>,
+    // motion + 일반 control 명령 (순서 보존을 위해 같은 lane)
+    control: VecDeque<(MessageClass, SignalMessage)>,
+    signaling: VecDeque<SignalMessage>,
+    closed: bool,
+    // 닫힌 뒤 남은 메시지까지 모두 꺼내졌는지 (outbound 스트림 종료)
+    finished: bool,
+}
+
+/// Gateway -> grpc-robot-api outbound 큐.
+/// priority lane은 제한 없이 항상 먼저 비우고, control/signaling lane은 `capacity`로 제한한다.
+pub struct OutboundQueue {
+    lanes: Mutex<Lanes>,
+    notify: Notify,
+    finished: Notify,
+    capacity: usize,
+    // true면 아직 나가지 않은 같은 robot의 MOVE/SET_SPEED/VELOCITY를 새 값으로 덮어쓴다 (latest-wins)
+    coalesce_motion: bool,
+    metrics: Arc<GatewayMetrics>,
+}
+
+impl OutboundQueue {
+    pub fn new(capacity: usize, coalesce_motion: bool, metrics: Arc<GatewayMetrics>) -> Self {
+        Self {
+            lanes: Mutex::new(Lanes::default()),
+            notify: Notify::new(),
+            finished: Notify::new(),
+            capacity: capacity.max(1),
+            coalesce_motion,
+            metrics,
+        }
+    }
+
+    /// 메시지를 큐에 넣는다. 새 메시지에 밀려 나가지 못하게 된 control 명령이 있으면
+    /// 그 command_id들을 돌려준다. (robot ack을 기다리는 세션에 알려야 한다)
+    pub fn push(&self, msg: SignalMessage) -> Result<Vec<String>, QueueError> {
+        let class = classify(&msg);
+        let mut lanes = self.lanes.lock().unwrap();
+        if lanes.closed {
+            return Err(QueueError::Closed);
+        }
+
+        // latest-wins: 대기 중인 같은 종류의 motion 명령을 새 값으로 교체 (자리를 차지하지 않음)
+        if class == MessageClass::Motion
+            && self.coalesce_motion
+            && let Some(pending) = Self::coalesce_target(&lanes, &msg)
+        {
+            let superseded = std::mem::replace(&mut lanes.control[pending].1, msg);
+            self.metrics.record_motion_coalesced();
+            return Ok(command_id(superseded).into_iter().collect());
+        }
+
+        let mut displaced = Vec::new();
+        match class {
+            MessageClass::Priority => {
+                // STOP/E-STOP은 앞질러 나가므로, 그보다 먼저 쌓인 같은 robot의 control 명령이
+                // 뒤따라 나가면 robot이 정지 후 다시 움직인다 (dock, navigate_to, resume 등 포함).
+                // 대기 중인 그 robot의 control 명령은 모두 버린다.
+                let robot_id = msg.robot_id.clone();
+                let mut kept = VecDeque::with_capacity(lanes.control.len());
+                for (class, pending) in lanes.control.drain(..) {
+                    if pending.robot_id == robot_id {
+                        self.metrics.record_motion_dropped();
+                        displaced.extend(command_id(pending));
+                    } else {
+                        kept.push_back((class, pending));
+                    }
+                }
+                lanes.control = kept;
+                lanes.priority.push_back(msg);
+            }
+            MessageClass::Motion | MessageClass::Command => {
+                if lanes.control.len() >= self.capacity {
+                    // 같은 robot의 가장 오래된 motion 명령을 버려 자리를 만든다. 없으면 거절.
+                    // (shared 스트림에서 한 robot의 flood가 다른 robot의 명령을 밀어내지 않도록)
+                    let Some(oldest_motion) = lanes.control.iter().position(|(class, pending)| {
+                        *class == MessageClass::Motion && pending.robot_id == msg.robot_id
+                    }) else {
+                        self.metrics.record_outbound_rejected();
+                        return Err(QueueError::Full);
+                    };
+                    if let Some((_, dropped)) = lanes.control.remove(oldest_motion) {
+                        displaced.extend(command_id(dropped));
+                    }
+                    self.metrics.record_motion_dropped();
+                }
+                lanes.control.push_back((class, msg));
+            }
+            MessageClass::Signaling => {
+                if lanes.signaling.len() >= self.capacity {
+                    self.metrics.record_outbound_rejected();
+                    return Err(QueueError::Full);
+                }
+                lanes.signaling.push_back(msg);
+            }
+        }
+        drop(lanes);
+
+        self.notify.notify_one();
+        Ok(displaced)
+    }
+
+    /// 덮어쓸 수 있는 대기 중인 motion 명령의 위치.
+    /// 같은 robot의 가장 마지막 control 명령이 같은 종류의 motion일 때만 덮어쓴다.
+    /// (그 뒤에 다른 종류의 motion이나 DOCK 등이 있으면 robot이 받는 순서가 바뀐다)
+    /// 다른 robot의 명령은 순서와 무관하므로 보지 않는다.
+    fn coalesce_target(lanes: &Lanes, msg: &SignalMessage) -> Option<usize> {
+        let key = motion_key(msg)?;
+        let (idx, (class, pending)) = lanes
+            .control
+            .iter()
+            .enumerate()
+            .rev()
+            .find(|(_, (_, pending))| pending.robot_id == msg.robot_id)?;
+        (*class == MessageClass::Motion && motion_key(pending) == Some(key)).then_some(idx)
+    }
+
+    /// 다음으로 보낼 메시지. 큐가 닫히고 남은 메시지도 모두 나가면 None.
+    pub async fn pop(&self) -> Option<SignalMessage> {
+        loop {
+            let notified = self.notify.notified();
+            {
+                let mut lanes = self.lanes.lock().unwrap();
+                if let Some(msg) = lanes.priority.pop_front() {
+                    return Some(msg);
+                }
+                if let Some((_, msg)) = lanes.control.pop_front() {
+                    return Some(msg);
+                }
+                if let Some(msg) = lanes.signaling.pop_front() {
+                    return Some(msg);
+                }
+                if lanes.closed {
+                    lanes.finished = true;
+                    self.finished.notify_waiters();
+                    return None;
+                }
+            }
+            notified.await;
+        }
+    }
+
+    /// 닫힌 뒤 남은 메시지까지 모두 나갔는지
+    pub fn is_finished(&self) -> bool {
+        self.lanes.lock().unwrap().finished
+    }
+
+    /// `close` 후 남은 메시지가 모두 pop될 때까지 기다린다. (종료 시 STOP flush 확인용)
+    pub async fn finished(&self) {
+        loop {
+            let notified = self.finished.notified();
+            if self.is_finished() {
+                return;
+            }
+            notified.await;
+        }
+    }
+
+    /// 더 이상 push를 받지 않는다. 이미 쌓인 메시지는 pop으로 계속 나간다.
+    pub fn close(&self) {
+        self.lanes.lock().unwrap().closed = true;
+        self.notify.notify_one();
+    }
+
+    pub fn depth(&self) -> QueueDepth {
+        let lanes = self.lanes.lock().unwrap();
+        QueueDepth {
+            priority: lanes.priority.len(),
+            control: lanes.control.len(),
+            signaling: lanes.signaling.len(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::protocol::robot::signaling::{ControlCommand, IceCandidate};
+
+    fn control(robot_id: &str, command: GrpcCommandType, command_id: &str) -> SignalMessage {
+        SignalMessage {
+            robot_id: robot_id.to_string(),
+            payload: Some(signal_message::Payload::ControlCommand(ControlCommand {
+                command: command as i32,
+                command_id: command_id.to_string(),
+                sequence: 0,
+                payload: None,
+            })),
+        }
+    }
+
+    fn ice(robot_id: &str, candidate: usize) -> SignalMessage {
+        SignalMessage {
+            robot_id: robot_id.to_string(),
+            payload: Some(signal_message::Payload::ClientIce(IceCandidate {
+                candidate: format!("candidate-{candidate}"),
+                sdp_mid: "0".to_string(),
+                sdp_mline_index: 0,
+            })),
+        }
+    }
+
+    fn queue(capacity: usize) -> OutboundQueue {
+        OutboundQueue::new(capacity, false, Arc::new(GatewayMetrics::default()))
+    }
+
+    /// 닫은 뒤 남은 메시지를 모두 꺼내 (command_id 또는 "ice") 순서로 돌려준다.
+    async fn drain(queue: &OutboundQueue) -> Vec<String> {
+        queue.close();
+        let mut out = Vec::new();
+        while let Some(msg) = queue.pop().await {
+            out.push(command_id(msg).unwrap_or_else(|| "ice".to_string()));
+        }
+        out
+    }
+
+    #[tokio::test]
+    async fn estop_is_delivered_before_flooded_signaling() {
+        let queue = queue(64);
+        for i in 0..64 {
+            queue.push(ice("robot-01", i)).unwrap();
+        }
+        // signaling lane이 가득 차도 e_stop은 거절되지 않는다.
+        assert_eq!(queue.push(ice("robot-01", 64)), Err(QueueError::Full));
+        queue
+            .push(control("robot-01", GrpcCommandType::EmergencyStop, "estop"))
+            .unwrap();
+
+        let out = drain(&queue).await;
+        assert_eq!(out.len(), 65);
+        assert_eq!(out[0], "estop");
+        assert!(out[1..].iter().all(|id| id == "ice"));
+    }
+
+    #[tokio::test]
+    async fn estop_drops_pending_commands_of_the_same_robot() {
+        let queue = queue(64);
+        for i in 0..10 {
+            queue.push(ice("robot-01", i)).unwrap();
+        }
+        queue.push(control("robot-01", GrpcCommandType::Move, "move-1")).unwrap();
+        queue.push(control("robot-01", GrpcCommandType::Dock, "dock-1")).unwrap();
+        queue.push(control("robot-02", GrpcCommandType::Move, "other-1")).unwrap();
+        queue.push(control("robot-01", GrpcCommandType::Velocity, "vel-1")).unwrap();
+
+        let displaced = queue
+            .push(control("robot-01", GrpcCommandType::EmergencyStop, "estop"))
+            .unwrap();
+        assert_eq!(displaced, ["move-1", "dock-1", "vel-1"]);
+
+        // e_stop 뒤에 robot-01의 control 명령은 남지 않는다. (다른 robot은 그대로)
+        let out = drain(&queue).await;
+        assert_eq!(out[..2], ["estop", "other-1"]);
+        assert!(out[2..].iter().all(|id| id == "ice"));
+        assert_eq!(out.len(), 12);
+    }
+
+    #[tokio::test]
+    async fn estop_is_never_followed_by_an_earlier_task_command() {
+        let queue = queue(64);
+        queue
+            .push(control("robot-01", GrpcCommandType::NavigateTo, "nav-1"))
+            .unwrap();
+        queue.push(control("robot-01", GrpcCommandType::Resume, "resume-1")).unwrap();
+        queue.push(control("robot-01", GrpcCommandType::Custom, "custom-1")).unwrap();
+
+        let displaced = queue
+            .push(control("robot-01", GrpcCommandType::EmergencyStop, "estop"))
+            .unwrap();
+        assert_eq!(displaced, ["nav-1", "resume-1", "custom-1"]);
+        assert_eq!(drain(&queue).await, ["estop"]);
+    }
+
+    #[test]
+    fn classification_matches_the_domain_motion_rule() {
+        for grpc in (0..=15).filter_map(|v| GrpcCommandType::try_from(v).ok()) {
+            let class = classify(&control("robot-01", grpc, "id"));
+            let Ok(command) = CommandType::try_from(grpc) else {
+                assert_eq!(class, MessageClass::Command);
+                continue;
+            };
+            match class {
+                MessageClass::Priority => assert!(command.is_stop() && !command.is_motion()),
+                MessageClass::Motion => assert!(command.is_motion()),
+                MessageClass::Command => assert!(!command.is_stop()),
+                MessageClass::Signaling => panic!("control command classified as signaling"),
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn finished_resolves_after_remaining_messages_are_popped() {
+        let queue = Arc::new(queue(4));
+        queue.push(control("robot-01", GrpcCommandType::Stop, "stop")).unwrap();
+        queue.close();
+        assert!(!queue.is_finished());
+
+        let waiter = tokio::spawn({
+            let queue = queue.clone();
+            async move { queue.finished().await }
+        });
+        assert_eq!(queue.pop().await.and_then(command_id).as_deref(), Some("stop"));
+        assert!(queue.pop().await.is_none());
+        waiter.await.unwrap();
+        assert!(queue.is_finished());
+    }
+
+    #[tokio::test]
+    async fn stop_also_drops_stale_motion() {
+        let queue = queue(4);
+        for i in 0..4 {
+            queue
+                .push(control("robot-01", GrpcCommandType::Move, &format!("move-{i}")))
+                .unwrap();
+        }
+        let displaced = queue.push(control("robot-01", GrpcCommandType::Stop, "stop")).unwrap();
+        assert_eq!(displaced.len(), 4);
+
+        queue.push(control("robot-01", GrpcCommandType::Move, "move-after")).unwrap();
+        assert_eq!(drain(&queue).await, ["stop", "move-after"]);
+    }
+
+    #[tokio::test]
+    async fn full_lane_drops_the_oldest_motion_of_the_same_robot() {
+        let metrics = Arc::new(GatewayMetrics::default());
+        let queue = OutboundQueue::new(3, false, metrics.clone());
+        queue.push(control("robot-02", GrpcCommandType::Move, "other-1")).unwrap();
+        queue.push(control("robot-01", GrpcCommandType::Move, "move-0")).unwrap();
+        queue.push(control("robot-01", GrpcCommandType::Move, "move-1")).unwrap();
+
+        let displaced = queue.push(control("robot-01", GrpcCommandType::Move, "move-2")).unwrap();
+        assert_eq!(displaced, ["move-0"]);
+        assert_eq!(metrics.snapshot().motion_dropped, 1);
+        assert_eq!(drain(&queue).await, ["other-1", "move-1", "move-2"]);
+    }
+
+    #[tokio::test]
+    async fn full_lane_never_drops_another_robots_motion() {
+        let metrics = Arc::new(GatewayMetrics::default());
+        let queue = OutboundQueue::new(2, false, metrics.clone());
+        queue.push(control("robot-02", GrpcCommandType::Move, "other-1")).unwrap();
+        queue.push(control("robot-02", GrpcCommandType::Velocity, "other-2")).unwrap();
+
+        assert_eq!(
+            queue.push(control("robot-01", GrpcCommandType::Move, "move-1")),
+            Err(QueueError::Full)
+        );
+        assert_eq!(
+            queue.push(control("robot-01", GrpcCommandType::Dock, "dock-1")),
+            Err(QueueError::Full)
+        );
+        assert_eq!(metrics.snapshot().motion_dropped, 0);
+        assert_eq!(metrics.snapshot().outbound_rejected, 2);
+        assert_eq!(drain(&queue).await, ["other-1", "other-2"]);
+    }
+
+    #[tokio::test]
+    async fn commands_and_signaling_are_rejected_when_full() {
+        let metrics = Arc::new(GatewayMetrics::default());
+        let queue = OutboundQueue::new(2, false, metrics.clone());
+        queue.push(control("robot-01", GrpcCommandType::Dock, "dock-1")).unwrap();
+        queue.push(control("robot-01", GrpcCommandType::PathFollow, "path-1")).unwrap();
+        queue.push(ice("robot-01", 0)).unwrap();
+        queue.push(ice("robot-01", 1)).unwrap();
+
+        // motion이 없는 control lane은 dock/motion 모두 거절, signaling lane도 따로 거절된다.
+        assert_eq!(
+            queue.push(control("robot-01", GrpcCommandType::Undock, "undock-1")),
+            Err(QueueError::Full)
+        );
+        assert_eq!(
+            queue.push(control("robot-01", GrpcCommandType::Move, "move-1")),
+            Err(QueueError::Full)
+        );
+        assert_eq!(queue.push(ice("robot-01", 2)), Err(QueueError::Full));
+        assert_eq!(metrics.snapshot().outbound_rejected, 3);
+
+        let depth = queue.depth();
+        assert_eq!((depth.control, depth.signaling), (2, 2));
+        assert_eq!(drain(&queue).await, ["dock-1", "path-1", "ice", "ice"]);
+    }
+
+    fn coalescing_queue(metrics: Arc<GatewayMetrics>) -> OutboundQueue {
+        OutboundQueue::new(64, true, metrics)
+    }
+
+    #[tokio::test]
+    async fn pending_motion_is_replaced_by_the_latest_value() {
+        let metrics = Arc::new(GatewayMetrics::default());
+        let queue = coalescing_queue(metrics.clone());
+        for kind in [GrpcCommandType::Move, GrpcCommandType::SetSpeed, GrpcCommandType::Velocity] {
+            let name = kind.as_str_name().to_lowercase();
+            queue.push(control("robot-01", kind, &format!("{name}-1"))).unwrap();
+            let superseded = queue.push(control("robot-01", kind, &format!("{name}-2"))).unwrap();
+            assert_eq!(superseded, [format!("{name}-1")]);
+        }
+
+        // 종류가 다른 motion끼리는 덮어쓰지 않는다.
+        assert_eq!(metrics.snapshot().motion_coalesced, 3);
+        assert_eq!(drain(&queue).await, ["move-2", "set_speed-2", "velocity-2"]);
+    }
+
+    #[tokio::test]
+    async fn motion_is_never_coalesced_across_a_queued_command() {
+        let queue = coalescing_queue(Arc::new(GatewayMetrics::default()));
+        queue.push(control("robot-01", GrpcCommandType::Move, "move-1")).unwrap();
+        queue.push(control("robot-01", GrpcCommandType::Dock, "dock-1")).unwrap();
+        assert!(queue.push(control("robot-01", GrpcCommandType::Move, "move-2")).unwrap().is_empty());
+        queue.push(control("robot-01", GrpcCommandType::PathFollow, "path-1")).unwrap();
+        assert!(queue.push(control("robot-01", GrpcCommandType::Move, "move-3")).unwrap().is_empty());
+
+        assert_eq!(drain(&queue).await, ["move-1", "dock-1", "move-2", "path-1", "move-3"]);
+    }
+
+    #[tokio::test]
+    async fn motion_is_never_coalesced_across_robots() {
+        let queue = coalescing_queue(Arc::new(GatewayMetrics::default()));
+        queue.push(control("robot-01", GrpcCommandType::Move, "move-1")).unwrap();
+        assert!(queue.push(control("robot-02", GrpcCommandType::Move, "other-1")).unwrap().is_empty());
+
+        // 다른 robot의 motion을 건너뛰어 자기 robot의 대기 중인 move만 교체한다.
+        let superseded = queue.push(control("robot-01", GrpcCommandType::Move, "move-2")).unwrap();
+        assert_eq!(superseded, ["move-1"]);
+        assert_eq!(drain(&queue).await, ["move-2", "other-1"]);
+    }
+
+    #[tokio::test]
+    async fn motion_is_not_coalesced_across_another_motion_kind() {
+        let queue = coalescing_queue(Arc::new(GatewayMetrics::default()));
+        queue.push(control("robot-01", GrpcCommandType::Move, "move-1")).unwrap();
+        queue.push(control("robot-01", GrpcCommandType::Velocity, "vel-1")).unwrap();
+
+        // move-1을 덮어쓰면 robot은 더 오래된 velocity로 끝난다.
+        assert!(queue.push(control("robot-01", GrpcCommandType::Move, "move-2")).unwrap().is_empty());
+        assert_eq!(drain(&queue).await, ["move-1", "vel-1", "move-2"]);
+    }
+
+    #[tokio::test]
+    async fn another_robots_command_does_not_block_coalescing() {
+        let queue = coalescing_queue(Arc::new(GatewayMetrics::default()));
+        queue.push(control("robot-01", GrpcCommandType::Move, "move-1")).unwrap();
+        queue.push(control("robot-02", GrpcCommandType::Dock, "dock-2")).unwrap();
+
+        let superseded = queue.push(control("robot-01", GrpcCommandType::Move, "move-2")).unwrap();
+        assert_eq!(superseded, ["move-1"]);
+        assert_eq!(drain(&queue).await, ["move-2", "dock-2"]);
+    }
+}
@@ -1,34 +1,62 @@
 use std::sync::Arc;
+use std::time::{SystemTime, UNIX_EPOCH};
 
 use anyhow::anyhow;
 use futures_util::{SinkExt, StreamExt};
 use serde_json::{json, Map, Value};
 use tokio::net::TcpStream;
-use tokio::sync::{mpsc, oneshot};
-use tokio::time::{self, Duration};
+use tokio::sync::{mpsc, oneshot, watch};
+use tokio::time::{self, Duration, Instant};
 use tokio_tungstenite::{
     accept_hdr_async,
-    tungstenite::handshake::server::{Request, Response},
+    tungstenite::handshake::server::{ErrorResponse, Request, Response},
+    tungstenite::http::header::{HeaderValue, SEC_WEBSOCKET_PROTOCOL, WWW_AUTHENTICATE},
+    tungstenite::http::StatusCode,
+    tungstenite::protocol::{frame::coding::CloseCode, CloseFrame},
     tungstenite::{Message, Bytes},
     WebSocketStream,
 };
 
-use crate::domain::control::{ControlRequest, ControlRequestType};
+use crate::app::metrics::GatewayMetrics;
+use crate::auth::{audit, Authenticator, Identity};
+use crate::config::configs::ControlConfig;
+use crate::domain::control::{
+    default_frame_id, AckMode, ControlErrorCode, ControlRequest, ControlRequestType, Direction, WsControlResponse,
+};
+use crate::domain::catalog::{CatalogError, CommandCatalog};
 use crate::domain::signal::{CommandType, ControlPayload, WsSignalMessage};
+use crate::domain::validation::validate_control_payload;
 use crate::protocol::grpc::GrpcClient;
-use crate::protocol::robot::signaling::SignalMessage;
-use crate::session::manager::SharedSessions;
+use crate::protocol::outbound_queue::QueueError;
+use crate::protocol::robot::signaling::{CommandResult, SignalMessage};
+use crate::session::lease::{LeaseError, LeaseNotice};
+use crate::session::manager::{SessionId, SharedSessions, WsReceiver};
+use crate::session::rate_limit::{self, RateLimitScope, SessionRateLimiter};
 
 type WsSink = futures_util::stream::SplitSink<WebSocketStream<TcpStream>, Message>;
 
+#[derive(Clone)]
 pub struct WebSocketHandler {
     grpc: Arc<GrpcClient>,
     sessions: SharedSessions,
+    control: Arc<ControlConfig>,
+    metrics: Arc<GatewayMetrics>,
+    catalog: Arc<CommandCatalog>,
+    auth: Arc<Authenticator>,
+    shutdown: watch::Receiver<bool>,
 }
 
 impl WebSocketHandler {
-    pub fn new(grpc: Arc<GrpcClient>, sessions: SharedSessions) -> Self {
-        Self { grpc, sessions }
+    pub fn new(
+        grpc: Arc<GrpcClient>,
+        sessions: SharedSessions,
+        control: Arc<ControlConfig>,
+        metrics: Arc<GatewayMetrics>,
+        catalog: Arc<CommandCatalog>,
+        auth: Arc<Authenticator>,
+        shutdown: watch::Receiver<bool>,
+    ) -> Self {
+        Self { grpc, sessions, control, metrics, catalog, auth, shutdown }
     }
 
     pub async fn handle_connection(&self, stream: TcpStream) -> anyhow::Result<()> {
@@ -40,21 +68,42 @@ impl WebSocketHandler {
         let (path_tx, path_rx) = oneshot::channel();
 
         // Capture the HTTP path during the WebSocket handshake so we can route control/screen channels.
-        let ws_stream = accept_hdr_async(stream, move |req: &Request, resp: Response| {
-            let _ = path_tx.send(req.uri().path().to_string());
-            Ok(resp)
+        // 토큰도 여기서 검증해서, 실패하면 upgrade 전에 401로 거절한다.
+        // The callback signature (Result<Response, ErrorResponse>) is dictated by tungstenite.
+        let auth = self.auth.clone();
+        let auth_peer = peer.clone();
+        #[allow(clippy::result_large_err)]
+        let ws_stream = accept_hdr_async(stream, move |req: &Request, mut resp: Response| {
+            let path = req.uri().path().to_string();
+            match auth.authenticate(req) {
+                Ok(authenticated) => {
+                    if let Some(protocol) = authenticated.protocol {
+                        // 클라이언트가 제안한 subprotocol을 돌려주지 않으면 브라우저가 연결을 끊는다.
+                        resp.headers_mut()
+                            .insert(SEC_WEBSOCKET_PROTOCOL, HeaderValue::from_static(protocol));
+                    }
+                    let _ = path_tx.send((path, authenticated.identity));
+                    Ok(resp)
+                }
+                Err(e) => {
+                    log::warn!("[auth] rejected handshake peer={auth_peer} path={path}: {e}");
+                    Err(unauthorized_response())
+                }
+            }
         })
         .await?;
 
-        let path = path_rx.await.unwrap_or_else(|_| "/".to_string());
-        log::info!("[ws] handshake peer={peer} path={path}");
+        let (path, identity) = path_rx
+            .await
+            .map_err(|_| anyhow!("handshake finished without identity"))?;
+        log::info!("[ws] handshake peer={peer} path={path} identity={identity}");
 
         if let Some(robot_id) = extract_robot_id(&path, "/ws/screen/") {
             log::info!("[ws] route=screen robot_id={robot_id}");
-            self.handle_screen_channel(robot_id, ws_stream).await
+            self.handle_screen_channel(robot_id, identity, ws_stream).await
         } else if let Some(robot_id) = extract_robot_id(&path, "/ws/control/") {
             log::info!("[ws] route=control robot_id={robot_id}");
-            self.handle_control_channel(robot_id, ws_stream).await
+            self.handle_control_channel(robot_id, identity, ws_stream).await
         } else {
             anyhow::bail!("unsupported websocket path: {path}");
         }
@@ -69,7 +118,7 @@ impl WebSocketHandler {
         // Ensure the bi-di stream is open and immediately send a handshake message
         // carrying only the robot_id so the gRPC server can bind the session.
         self.grpc
-            .ensure_signal_stream(self.sessions.clone(), Some(hello))
+            .ensure_signal_stream(self.sessions.clone(), robot_id, Some(hello))
             .await?;
         log::info!("[ws] sent initial signaling handshake for {robot_id}");
 
@@ -79,18 +128,47 @@ impl WebSocketHandler {
     async fn handle_screen_channel(
         &self,
         robot_id: String,
+        identity: Identity,
         ws_stream: WebSocketStream<TcpStream>,
     ) -> anyhow::Result<()> {
-        log::info!("[screen] handle_screen_channel enter robot_id={robot_id}");
-        let (ws_sink, mut ws_stream) = ws_stream.split();
+        log::info!("[screen] handle_screen_channel enter robot_id={robot_id} identity={identity}");
+
+        if !self.auth.policy().can_view(&identity, &robot_id) {
+            log::warn!("[auth] screen access denied robot_id={robot_id} identity={identity}");
+            audit(&identity, &robot_id, "open_screen", "denied");
+            return close_forbidden(ws_stream, "screen access denied").await;
+        }
+        audit(&identity, &robot_id, "open_screen", "allowed");
+
+        // gRPC -> WS 송신 큐 (WebRTC signaling, bounded)
+        let (session_id, ws_rx) = {
+            let mut guard = self.sessions.write().await;
+            guard.insert(robot_id.clone())
+        };
+        log::info!("[screen] viewer registered robot_id={robot_id} session_id={session_id}");
+        self.grpc.acquire_signal_stream(&robot_id).await;
 
-        // gRPC -> WS 송신 큐 (WebRTC signaling)
-        let (ws_tx, mut ws_rx) = mpsc::unbounded_channel::<SignalMessage>();
+        let result = self.run_screen_channel(&robot_id, ws_stream, ws_rx).await;
 
+        // 이 viewer 세션만 제거 (같은 robot의 다른 viewer는 유지)
         {
             let mut guard = self.sessions.write().await;
-            guard.insert(robot_id.clone(), ws_tx);
+            guard.remove(&robot_id, session_id);
         }
+        // 이 세션이 마지막 사용자일 때만 gRPC signaling 스트림이 정리된다.
+        self.grpc.release_signal_stream(&robot_id).await;
+
+        result
+    }
+
+    async fn run_screen_channel(
+        &self,
+        robot_id: &str,
+        ws_stream: WebSocketStream<TcpStream>,
+        mut ws_rx: WsReceiver,
+    ) -> anyhow::Result<()> {
+        let robot_id = robot_id.to_string();
+        let (ws_sink, mut ws_stream) = ws_stream.split();
 
         // gRPC signal stream을 즉시 준비시키고 handshake 메시지를 전송
         self.init_signaling(&robot_id)
@@ -111,12 +189,17 @@ impl WebSocketHandler {
                 tokio::select! {
                     _ = ping_interval.tick() => {
                         if let Err(e) = ws_sink.send(Message::Ping(Bytes::new())).await {
-                            eprintln!("[screen] ping failed for {}: {}", robot_id_for_task, e);
+                            log::warn!("[screen] ping failed for {robot_id_for_task}: {e}");
                             break;
                         }
                     }
                     msg = ws_rx.recv() => {
-                        let Some(msg) = msg else { break; };
+                        let Some(msg) = msg else {
+                            // 큐가 넘쳐 세션에서 제거된 느린 viewer: 소켓을 닫아 재접속하게 한다.
+                            log::warn!("[screen] viewer queue closed for {}, closing socket", robot_id_for_task);
+                            let _ = ws_sink.send(Message::Close(None)).await;
+                            break;
+                        };
 
                         // 서버 keepalive 등 payload가 비어 있으면 건너뛴다.
                         if msg.payload.is_none() {
@@ -135,7 +218,7 @@ impl WebSocketHandler {
                         let ws_msg = match WsSignalMessage::try_from(msg) {
                             Ok(v) => v,
                             Err(e) => {
-                                eprintln!("convert error: {e}");
+                                log::warn!("[screen] failed to convert signal for {robot_id_for_task}: {e}");
                                 break;
                             }
                         };
@@ -143,7 +226,7 @@ impl WebSocketHandler {
                         let json = match serde_json::to_string(&ws_msg) {
                             Ok(v) => v,
                             Err(e) => {
-                                eprintln!("serde error: {e}");
+                                log::warn!("[screen] failed to serialize signal for {robot_id_for_task}: {e}");
                                 break;
                             }
                         };
@@ -157,20 +240,49 @@ impl WebSocketHandler {
         });
 
         // WS -> gRPC (WsSignalMessage -> SignalMessage -> signal_tx send)
-        while let Some(Ok(msg)) = ws_stream.next().await {
+        let mut shutdown = self.shutdown.clone();
+        loop {
+            let msg = tokio::select! {
+                _ = shutdown.changed() => {
+                    log::info!("[screen] gateway shutting down, closing screen session for {robot_id}");
+                    break;
+                }
+                msg = ws_stream.next() => msg,
+            };
+            let Some(Ok(msg)) = msg else { break; };
+
             match msg {
                 Message::Text(text) => {
                     log::info!("[screen] inbound text from client robot_id={}: {text}", robot_id);
                     let ws_msg: WsSignalMessage = serde_json::from_str(text.as_str())?;
+                    // screen 채널은 WebRTC signaling만 받는다. control 명령은 /ws/control의
+                    // 권한/lease/e_stop/rate limit 검사를 거쳐야 하므로 여기서는 거절한다.
+                    match screen_signal_robot_id(&ws_msg) {
+                        Some(target) if target == robot_id => {}
+                        Some(target) => {
+                            log::warn!("[screen] dropped signal for {target} on screen channel of {robot_id}");
+                            continue;
+                        }
+                        None => {
+                            log::warn!("[screen] dropped non-signaling message on screen channel of {robot_id}");
+                            continue;
+                        }
+                    }
                     let signal: SignalMessage = ws_msg.try_into()?;
 
                     let mut sent = false;
                     // 1차 시도
-                    if let Ok(sender) = self.grpc.signal_sender().await {
-                        if sender.send(signal.clone()).is_ok() {
-                            sent = true;
-                        } else {
-                            log::warn!("[screen] failed to send signal to gRPC for {}: channel closed (retrying)", robot_id);
+                    if let Ok(sender) = self.grpc.signal_sender(&robot_id).await {
+                        match sender.send(signal.clone()) {
+                            Ok(_) => sent = true,
+                            Err(QueueError::Full) => {
+                                // 스트림은 살아 있으므로 재연결하지 않고 이 메시지만 버린다.
+                                log::warn!("[screen] gRPC outbound queue full for {}, dropping signal", robot_id);
+                                continue;
+                            }
+                            Err(QueueError::Closed) => {
+                                log::warn!("[screen] failed to send signal to gRPC for {}: channel closed (retrying)", robot_id);
+                            }
                         }
                     }
 
@@ -178,11 +290,11 @@ impl WebSocketHandler {
                     if !sent {
                         if let Err(e) = self.init_signaling(&robot_id).await {
                             log::warn!("[screen] retry init signaling failed for {}: {}", robot_id, e);
-                        } else if let Ok(sender) = self.grpc.signal_sender().await {
-                            if sender.send(signal).is_ok() {
-                                sent = true;
-                                log::info!("[screen] resent signal after reconnect for {}", robot_id);
-                            }
+                        } else if let Ok(sender) = self.grpc.signal_sender(&robot_id).await
+                            && sender.send(signal).is_ok()
+                        {
+                            sent = true;
+                            log::info!("[screen] resent signal after reconnect for {}", robot_id);
                         }
                     }
 
@@ -198,24 +310,274 @@ impl WebSocketHandler {
             }
         }
 
-        // 세션 제거
-        {
-            let mut guard = self.sessions.write().await;
-            guard.remove(&robot_id);
-        }
-        // WS 종료 시 gRPC signaling 스트림도 정리
-        self.grpc.close_signal_stream().await;
-
         Ok(())
     }
 
     async fn handle_control_channel(
         &self,
         robot_id: String,
+        identity: Identity,
         ws_stream: WebSocketStream<TcpStream>,
     ) -> anyhow::Result<()> {
-        log::info!("[control] handle_control_channel enter robot_id={robot_id}");
+        log::info!("[control] handle_control_channel enter robot_id={robot_id} identity={identity}");
+
+        if !self.auth.policy().can_control(&identity, &robot_id) {
+            log::warn!("[auth] control access denied robot_id={robot_id} identity={identity}");
+            audit(&identity, &robot_id, "open_control", "denied");
+            return close_forbidden(ws_stream, "control access denied").await;
+        }
+        audit(&identity, &robot_id, "open_control", "allowed");
+
+        // lease 상태 변경 알림을 받을 채널과 함께 control 세션 등록
+        let (lease_tx, lease_rx) = mpsc::unbounded_channel::<LeaseNotice>();
+        let session_id = {
+            let mut guard = self.sessions.write().await;
+            let session_id = guard.next_session_id();
+            guard.leases_mut().register(&robot_id, session_id, lease_tx);
+            session_id
+        };
+        log::info!("[control] operator registered robot_id={robot_id} session_id={session_id}");
+        self.grpc.acquire_signal_stream(&robot_id).await;
+
+        let result = self
+            .run_control_channel(&robot_id, session_id, &identity, ws_stream, lease_rx)
+            .await;
+
+        // Dead-man: 세션이 비정상 종료되면 robot이 마지막 명령을 계속 수행하지 않도록 STOP을 보낸다.
+        // (스트림 release 전에 보내야 마지막 세션이어도 STOP이 전달된다)
+        let mut stop_sent = false;
+        if let Ok(exit) = &result {
+            log::info!("[control] session ended robot_id={robot_id} session_id={session_id} exit={exit:?}");
+            if exit.is_abnormal()
+                && self.control.stop_on_disconnect
+                && self.drives_robot(&robot_id, session_id).await
+            {
+                stop_sent = self.send_gateway_stop(&robot_id, "dead-man").await;
+            }
+        }
+        self.finish_timed_stop(&robot_id, session_id, stop_sent).await;
+
+        {
+            let mut guard = self.sessions.write().await;
+            guard.leases_mut().unregister(&robot_id, session_id);
+        }
+
+        // 이 세션이 마지막 사용자일 때만 gRPC signaling 스트림이 정리된다.
+        self.grpc.release_signal_stream(&robot_id).await;
+
+        result.map(|_| ())
+    }
+
+    /// 이 세션이 robot을 움직이고 있을 수 있는지 (lease 모드가 아니면 모든 세션, lease 모드면 보유자만)
+    async fn drives_robot(&self, robot_id: &str, session_id: SessionId) -> bool {
+        !self.control.exclusive_lease
+            || self.sessions.read().await.leases().is_holder(robot_id, session_id)
+    }
+
+    /// 보유자의 heartbeat나 전송된 명령으로 lease ttl을 다시 시작한다. (lease 모드에서만)
+    async fn renew_lease(&self, robot_id: &str, session_id: SessionId) {
+        if !self.control.exclusive_lease {
+            return;
+        }
+        self.sessions
+            .write()
+            .await
+            .leases_mut()
+            .renew(robot_id, session_id, Instant::now());
+    }
+
+    /// request_control / release_control / handover_control 처리. 성공 시 ack 메시지를 돌려준다.
+    async fn handle_lease_request(
+        &self,
+        robot_id: &str,
+        session_id: SessionId,
+        req: &ControlRequest,
+    ) -> Result<String, (ControlErrorCode, String)> {
+        if !self.control.exclusive_lease {
+            return Err((
+                ControlErrorCode::LeaseDisabled,
+                "control lease is not enabled on this gateway".to_string(),
+            ));
+        }
+        let rejected = |e: LeaseError| (e.code(), e.to_string());
+
+        let now = Instant::now();
+        let mut guard = self.sessions.write().await;
+        let leases = guard.leases_mut();
+
+        match req.kind {
+            ControlRequestType::RequestControl => {
+                leases.request(robot_id, session_id, now).map_err(rejected)?;
+                Ok("control lease granted".to_string())
+            }
+            ControlRequestType::ReleaseControl => {
+                leases.release(robot_id, session_id, now).map_err(rejected)?;
+                Ok("control lease released".to_string())
+            }
+            ControlRequestType::HandoverControl => {
+                let to = req
+                    .payload
+                    .get("session_id")
+                    .and_then(Value::as_u64)
+                    .ok_or_else(|| {
+                        (
+                            ControlErrorCode::InvalidPayload,
+                            "session_id is required for handover_control".to_string(),
+                        )
+                    })?;
+                leases.handover(robot_id, session_id, to, now).map_err(rejected)?;
+                Ok(format!("control lease handed over to session {to}"))
+            }
+            _ => Err((ControlErrorCode::InvalidPayload, "not a lease request".to_string())),
+        }
+    }
+
+    /// robot의 CommandResult를 제한 시간 동안 기다렸다가 결과를 control loop로 넘긴다.
+    fn wait_command_result(
+        &self,
+        command_id: String,
+        rx: oneshot::Receiver<CommandResult>,
+        request_id: Option<String>,
+        outcome_tx: mpsc::UnboundedSender<CommandOutcome>,
+    ) {
+        let sessions = self.sessions.clone();
+        let timeout = Duration::from_millis(self.control.command_ack_timeout_ms);
+
+        tokio::spawn(async move {
+            let outcome = match time::timeout(timeout, rx).await {
+                Ok(Ok(result)) => CommandOutcome::Completed { result, request_id },
+                _ => {
+                    sessions.write().await.cancel_command(&command_id);
+                    CommandOutcome::TimedOut { command_id, request_id }
+                }
+            };
+            let _ = outcome_tx.send(outcome);
+        });
+    }
+
+    /// control 명령을 signaling 스트림으로 보낸다. 채널이 닫혀 있으면 한 번 재연결 후 재시도한다.
+    /// 큐가 가득 찬 경우(`QueueError::Full`)는 스트림이 살아 있으므로 재연결하지 않는다.
+    async fn forward_control_signal(&self, robot_id: &str, signal: SignalMessage) -> Result<(), QueueError> {
+        if let Ok(sender) = self.grpc.signal_sender(robot_id).await {
+            match sender.send(signal.clone()) {
+                Ok(displaced) => {
+                    self.notify_displaced(robot_id, displaced).await;
+                    return Ok(());
+                }
+                Err(QueueError::Full) => return Err(QueueError::Full),
+                Err(QueueError::Closed) => log::warn!("[control] gRPC channel closed for {robot_id}, retrying"),
+            }
+        }
+
+        if let Err(e) = self.init_signaling(robot_id).await {
+            log::warn!("[control] retry init signaling failed for {robot_id}: {e}");
+        } else if let Ok(sender) = self.grpc.signal_sender(robot_id).await {
+            let displaced = sender.send(signal)?;
+            self.notify_displaced(robot_id, displaced).await;
+            log::info!("[control] resent signal after reconnect for {robot_id}");
+            return Ok(());
+        }
+
+        Err(QueueError::Closed)
+    }
+
+    /// 시간 제한 move: `duration` 뒤 gateway가 STOP을 보낸다.
+    /// 예약한 control 세션이 끝나면 timer를 취소하고 바로 STOP을 보내며(`finish_timed_stop`),
+    /// timer가 도는 동안 signaling 스트림을 붙잡아 둔다.
+    async fn schedule_timed_stop(&self, robot_id: &str, session_id: SessionId, duration: Duration) {
+        let (timer_id, cancelled) = self.sessions.write().await.schedule_motion_stop(robot_id, session_id);
+        self.grpc.acquire_signal_stream(robot_id).await;
+        log::info!("[control] timed move for {robot_id}: STOP scheduled in {duration:?}");
+
+        let handler = self.clone();
+        let robot_id = robot_id.to_string();
+        tokio::spawn(async move {
+            if wait_motion_stop(&handler.sessions, &robot_id, timer_id, duration, cancelled).await {
+                handler.send_gateway_stop(&robot_id, "timed move").await;
+            } else {
+                log::debug!("[control] timed move STOP for {robot_id} cancelled (timer_id={timer_id})");
+            }
+            handler.grpc.release_signal_stream(&robot_id).await;
+        });
+    }
+
+    /// control 세션 종료 시 이 세션이 예약한 시간 제한 move STOP을 정리한다.
+    /// dead-man STOP을 이미 보냈으면 취소만 하고, 아니면 timer를 기다리지 않고 바로 STOP을 보낸다.
+    async fn finish_timed_stop(&self, robot_id: &str, session_id: SessionId, stop_sent: bool) {
+        if !self.sessions.write().await.cancel_session_motion_stop(robot_id, session_id) {
+            return;
+        }
+        log::info!("[control] timed move STOP for {robot_id} cancelled by session end (session_id={session_id})");
+        if !stop_sent {
+            self.send_gateway_stop(robot_id, "timed move (session ended)").await;
+        }
+    }
+
+    /// 세션/명령 종류/robot 한도를 확인하고 통과하면 토큰을 소비한다. (stop/e_stop 제외)
+    async fn check_rate_limit(
+        &self,
+        robot_id: &str,
+        limiter: &mut SessionRateLimiter,
+        kind: ControlRequestType,
+    ) -> Result<(), RateLimitScope> {
+        let mut sessions = self.sessions.write().await;
+        rate_limit::take_command(limiter, sessions.robot_rate_mut(), robot_id, kind, Instant::now())
+    }
+
+    /// 큐에서 더 새로운 명령(또는 STOP/E-STOP)에 밀려 전송되지 않은 motion 명령을 정리한다.
+    /// robot ack을 기다리던 세션에는 실패 결과로 알린다.
+    async fn notify_displaced(&self, robot_id: &str, displaced: Vec<String>) {
+        if displaced.is_empty() {
+            return;
+        }
+        let mut sessions = self.sessions.write().await;
+        for command_id in displaced {
+            log::debug!("[control] command_id={command_id} for {robot_id} superseded before it was sent");
+            sessions.complete_command(CommandResult {
+                command_id,
+                success: false,
+                message: "superseded by a newer command before it was sent".to_string(),
+            });
+        }
+    }
+
+    /// 클라이언트 요청 없이 gateway가 직접 STOP을 보낸다. (dead-man, heartbeat watchdog 등)
+    async fn send_gateway_stop(&self, robot_id: &str, reason: &str) -> bool {
+        let stop = WsSignalMessage::ControlCommand {
+            robot_id: robot_id.to_string(),
+            command: CommandType::Stop,
+            command_id: None,
+            sequence: None,
+            payload: None,
+        };
+
+        let delivered = match SignalMessage::try_from(stop) {
+            Ok(signal) => self.forward_control_signal(robot_id, signal).await.is_ok(),
+            Err(e) => {
+                log::error!("[control] failed to build {reason} STOP for {robot_id}: {e}");
+                false
+            }
+        };
+
+        if delivered {
+            log::warn!("[control] {reason} STOP sent for {robot_id}");
+        } else {
+            log::error!("[control] failed to deliver {reason} STOP for {robot_id}");
+        }
+        delivered
+    }
+
+    async fn run_control_channel(
+        &self,
+        robot_id: &str,
+        session_id: SessionId,
+        identity: &Identity,
+        ws_stream: WebSocketStream<TcpStream>,
+        mut lease_rx: mpsc::UnboundedReceiver<LeaseNotice>,
+    ) -> anyhow::Result<ControlExit> {
+        let robot_id = robot_id.to_string();
         let (mut ws_sink, mut ws_stream) = ws_stream.split();
+        let mut shutdown = self.shutdown.clone();
 
         // Control도 signaling stream을 통해 robot-api로 전달한다. (비동기 준비)
         self.init_signaling(&robot_id)
@@ -227,36 +589,316 @@ impl WebSocketHandler {
         log::info!("[control] signaling stream ready for {robot_id}");
 
         log::info!("[control] ws connected for robot_id={robot_id}, signaling stream ready");
-        if let Err(e) = send_control_ack(&mut ws_sink, "control channel ready").await {
-            eprintln!("[control] failed to send ready ack for {robot_id}: {e}");
+        if let Err(e) = send_control_ack(&mut ws_sink, &robot_id, None, "control channel ready").await {
+            log::warn!("[control] failed to send ready ack for {robot_id}: {e}");
+        }
+        if self.control.exclusive_lease {
+            // 자신의 session_id와 현재 보유자를 알려준다. (handover 대상 지정에 사용)
+            let holder = self.sessions.read().await.leases().holder(&robot_id, Instant::now());
+            let notice = LeaseNotice { holder, reason: "current" };
+            if let Err(e) = send_lease_notice(&mut ws_sink, &robot_id, session_id, &notice).await {
+                log::warn!("[control] failed to send lease state for {robot_id}: {e}");
+            }
         }
 
         let mut ping_interval = time::interval(Duration::from_secs(20));
+        let pong_timeout = Duration::from_secs(self.control.pong_timeout_secs);
+        let mut last_pong = Instant::now();
+        let mut exit = ControlExit::SocketError;
+
+        // heartbeat watchdog: 설정 시 클라이언트는 창(window) 안에 heartbeat 또는 새 move를 보내야 한다.
+        // 이 세션이 move/velocity를 처음 보낸 뒤부터 센다. (명령 없이 연결만 한 세션은 STOP을 보내지 않는다)
+        let heartbeat_window = (self.control.heartbeat_timeout_ms > 0)
+            .then(|| Duration::from_millis(self.control.heartbeat_timeout_ms));
+        let mut heartbeat_deadline: Option<Instant> = None;
+
+        // robot-level ack 결과 (대기 task -> control loop)
+        let (outcome_tx, mut outcome_rx) = mpsc::unbounded_channel::<CommandOutcome>();
+        let mut command_seq: u64 = 0;
+        // 클라이언트가 보낸 마지막 seq (순서 역전 판단용)
+        let mut last_seq: Option<u64> = None;
+        // 세션/명령 종류별 전송 한도
+        let mut rate_limiter = SessionRateLimiter::new(&self.control.rate_limit, Instant::now());
 
         loop {
             tokio::select! {
+                _ = shutdown.changed() => {
+                    log::info!("[control] gateway shutting down, closing control session for {robot_id}");
+                    exit = ControlExit::Shutdown;
+                    break;
+                }
                 _ = ping_interval.tick() => {
+                    if last_pong.elapsed() > pong_timeout {
+                        log::warn!("[control] no pong from client for {robot_id} within {:?}", pong_timeout);
+                        exit = ControlExit::PongTimeout;
+                        break;
+                    }
+                    // 명령이 없어도 만료를 알리도록 주기적으로 확인한다.
+                    if self.control.exclusive_lease {
+                        self.sessions.write().await.leases_mut().expire(&robot_id, Instant::now());
+                    }
                     if let Err(e) = ws_sink.send(Message::Ping(Bytes::new())).await {
-                        eprintln!("[control] ping failed for {robot_id}: {e}");
+                        log::warn!("[control] ping failed for {robot_id}: {e}");
                         break;
                     }
                 }
+                _ = time::sleep_until(heartbeat_deadline.unwrap_or_else(Instant::now)), if heartbeat_deadline.is_some() => {
+                    log::warn!("[control] heartbeat window lapsed for {robot_id}");
+                    // 다음 move/velocity가 올 때까지 다시 무장하지 않아 STOP이 반복되지 않게 한다.
+                    heartbeat_deadline = None;
+                    if self.drives_robot(&robot_id, session_id).await {
+                        self.send_gateway_stop(&robot_id, "heartbeat watchdog").await;
+                        let _ = send_control_error(&mut ws_sink, &robot_id, None, "heartbeat timeout: robot stopped").await;
+                    }
+                }
+                Some(outcome) = outcome_rx.recv() => {
+                    let sent = match outcome {
+                        CommandOutcome::Completed { result, request_id } if result.success => {
+                            send_control_ack(
+                                &mut ws_sink,
+                                &robot_id,
+                                request_id.as_deref(),
+                                format!("command {} executed by robot", result.command_id),
+                            )
+                            .await
+                        }
+                        CommandOutcome::Completed { result, request_id } => {
+                            send_control_error(
+                                &mut ws_sink,
+                                &robot_id,
+                                request_id.as_deref(),
+                                format!("command {} failed on robot: {}", result.command_id, result.message),
+                            )
+                            .await
+                        }
+                        CommandOutcome::TimedOut { command_id, request_id } => {
+                            log::warn!("[control] no CommandResult for {command_id} from {robot_id}");
+                            send_control_error(
+                                &mut ws_sink,
+                                &robot_id,
+                                request_id.as_deref(),
+                                format!(
+                                    "command {command_id} not acknowledged by robot within {}ms",
+                                    self.control.command_ack_timeout_ms
+                                ),
+                            )
+                            .await
+                        }
+                    };
+                    if let Err(e) = sent {
+                        log::warn!("[control] failed to send command result to client for {robot_id}: {e}");
+                    }
+                }
+                Some(notice) = lease_rx.recv() => {
+                    if let Err(e) = send_lease_notice(&mut ws_sink, &robot_id, session_id, &notice).await {
+                        log::warn!("[control] failed to send lease notice for {robot_id}: {e}");
+                    }
+                }
                 msg = ws_stream.next() => {
                     let Some(msg) = msg else { break; };
                     match msg {
                         Ok(Message::Text(text)) => {
                             log::info!("[control] recv raw text for {robot_id}: {text}");
 
-                            let ws_signal = match parse_control_request(&text, &robot_id) {
+                            let req: ControlRequest = match serde_json::from_str(&text) {
+                                Ok(req) => req,
+                                Err(e) => {
+                                    // 형식이 틀린 요청이라도 request_id는 최대한 돌려준다.
+                                    let request_id = extract_request_id(&text);
+                                    let _ = send_control_error(&mut ws_sink, &robot_id, request_id.as_deref(), e.to_string()).await;
+                                    log::info!("[control] parse error for {robot_id}: {e}");
+                                    continue;
+                                }
+                            };
+                            let request_id = req.request_id.clone();
+                            let seq = req.seq;
+
+                            if let Err((code, message)) =
+                                check_command_freshness(&req, last_seq, self.control.max_command_age_ms)
+                            {
+                                log::info!("[control] stale command for {robot_id} ({code:?}): {message}");
+                                let _ = send_control_error_with_code(
+                                    &mut ws_sink,
+                                    &robot_id,
+                                    request_id.as_deref(),
+                                    code,
+                                    message,
+                                )
+                                .await;
+                                continue;
+                            }
+
+                            if matches!(req.kind, ControlRequestType::Heartbeat) {
+                                log::debug!("[control] heartbeat from client for {robot_id}");
+                                // heartbeat는 이미 시작된 deadline만 연장한다.
+                                if let Some(window) = heartbeat_window
+                                    && heartbeat_deadline.is_some()
+                                {
+                                    heartbeat_deadline = Some(Instant::now() + window);
+                                }
+                                self.renew_lease(&robot_id, session_id).await;
+                                accept_seq(&mut last_seq, seq);
+                                continue;
+                            }
+
+                            if req.kind.is_lease_request() {
+                                let _ = match self.handle_lease_request(&robot_id, session_id, &req).await {
+                                    Ok(message) => {
+                                        accept_seq(&mut last_seq, seq);
+                                        send_control_ack(&mut ws_sink, &robot_id, request_id.as_deref(), message).await
+                                    }
+                                    Err((code, message)) => {
+                                        send_control_error_with_code(&mut ws_sink, &robot_id, request_id.as_deref(), code, message).await
+                                    }
+                                };
+                                continue;
+                            }
+
+                            // role/사용자별 명령 권한 (e_stop은 예외)
+                            if !self.auth.policy().can_send(identity, &robot_id, req.kind) {
+                                log::warn!("[auth] {:?} denied for {robot_id} identity={identity}", req.kind);
+                                audit(identity, &robot_id, format_args!("{:?}", req.kind), "denied");
+                                let _ = send_control_error_with_code(
+                                    &mut ws_sink,
+                                    &robot_id,
+                                    request_id.as_deref(),
+                                    ControlErrorCode::Forbidden,
+                                    format!("{:?} is not allowed for this client", req.kind),
+                                )
+                                .await;
+                                continue;
+                            }
+
+                            // lease 모드에서는 보유자만 제어 가능 (e_stop은 예외)
+                            if self.control.exclusive_lease && !req.kind.is_lease_exempt() {
+                                let checked = self
+                                    .sessions
+                                    .write()
+                                    .await
+                                    .leases_mut()
+                                    .check(&robot_id, session_id, Instant::now());
+                                if let Err(e) = checked {
+                                    log::info!("[control] rejected command from session {session_id} for {robot_id}: {e}");
+                                    let _ = send_control_error_with_code(
+                                        &mut ws_sink,
+                                        &robot_id,
+                                        request_id.as_deref(),
+                                        e.code(),
+                                        e.to_string(),
+                                    )
+                                    .await;
+                                    continue;
+                                }
+                            }
+
+                            let kind = req.kind;
+                            // e_stop latch: reset_estop 전까지 motion 명령 거절
+                            if self.sessions.read().await.is_blocked_by_estop(&robot_id, kind) {
+                                log::info!("[control] rejected {kind:?} for {robot_id}: emergency stop latched");
+                                let _ = send_control_error_with_code(
+                                    &mut ws_sink,
+                                    &robot_id,
+                                    request_id.as_deref(),
+                                    ControlErrorCode::EstopLatched,
+                                    "emergency stop is latched (send reset_estop first)",
+                                )
+                                .await;
+                                continue;
+                            }
+                            if kind == ControlRequestType::ResetEstop
+                                && !self
+                                    .control
+                                    .can_reset_estop(self.auth.is_enabled(), identity.role.as_deref())
+                            {
+                                let _ = send_control_error_with_code(
+                                    &mut ws_sink,
+                                    &robot_id,
+                                    request_id.as_deref(),
+                                    ControlErrorCode::Forbidden,
+                                    "reset_estop is not allowed for this client",
+                                )
+                                .await;
+                                continue;
+                            }
+                            if kind == ControlRequestType::EStop {
+                                // 전달 성공 여부와 무관하게 먼저 latch를 건다.
+                                self.sessions.write().await.latch_estop(&robot_id);
+                                log::warn!("[control] emergency stop latched for {robot_id}");
+                            }
+
+                            let ack_mode = req.ack;
+                            command_seq += 1;
+                            let command_id = format!("{session_id}-{command_seq}");
+
+                            let mut ws_signal = match parse_control_request(req, &robot_id, command_id.clone()) {
                                 Ok(msg) => msg,
                                 Err(e) => {
-                                    let _ = send_control_error(&mut ws_sink, e.to_string()).await;
-                                    eprintln!("[control] parse error for {robot_id}: {e}");
+                                    let _ = send_control_error_with_code(
+                                        &mut ws_sink,
+                                        &robot_id,
+                                        request_id.as_deref(),
+                                        ControlErrorCode::InvalidPayload,
+                                        e.to_string(),
+                                    )
+                                    .await;
+                                    log::info!("[control] parse error for {robot_id}: {e}");
                                     continue;
                                 }
                             };
 
+                            if let WsSignalMessage::ControlCommand { payload: Some(payload), .. } = &mut ws_signal {
+                                let limits = &self.control.limits;
+                                let cap = limits.cap_for(&robot_id, identity.role.as_deref());
+                                match validate_control_payload(payload, cap, limits) {
+                                    Ok(true) => log::info!(
+                                        "[control] command limited for {robot_id} (speed {cap}, angular {}, duration {}ms)",
+                                        limits.max_angular_speed,
+                                        limits.max_move_duration_ms
+                                    ),
+                                    Ok(false) => {}
+                                    Err(e) => {
+                                        log::info!("[control] invalid command for {robot_id}: {e}");
+                                        let _ = send_control_error_with_code(
+                                            &mut ws_sink,
+                                            &robot_id,
+                                            request_id.as_deref(),
+                                            e.code,
+                                            e.message,
+                                        )
+                                        .await;
+                                        continue;
+                                    }
+                                }
+
+                                // vendor/custom 명령: 카탈로그에 선언된 이름이면 params를 schema로 검사
+                                let checked = self.catalog.check_payload(&robot_id, payload);
+                                if let Err(e) = checked {
+                                    log::info!("[control] rejected {kind:?} command for {robot_id}: {e}");
+                                    let code = match e {
+                                        CatalogError::InvalidParams { .. } => ControlErrorCode::InvalidPayload,
+                                        _ => ControlErrorCode::UnknownCommand,
+                                    };
+                                    let _ = send_control_error_with_details(
+                                        &mut ws_sink,
+                                        &robot_id,
+                                        request_id.as_deref(),
+                                        code,
+                                        e.to_string(),
+                                        Some(e.details()),
+                                    )
+                                    .await;
+                                    continue;
+                                }
+                            }
+
                             log::info!("[control] parsed WsSignalMessage for {robot_id}: {:?}", ws_signal);
+                            let timed_move = match &ws_signal {
+                                WsSignalMessage::ControlCommand {
+                                    payload: Some(ControlPayload::Move { duration_ms: Some(ms), .. }),
+                                    ..
+                                } => Some(Duration::from_millis(*ms)),
+                                _ => None,
+                            };
 
                             match SignalMessage::try_from(ws_signal) {
                                 Ok(signal) => {
@@ -265,63 +907,118 @@ impl WebSocketHandler {
                                         signal.payload
                                     );
 
-                                    let mut delivered = false;
-                                    if let Ok(sender) = self.grpc.signal_sender().await {
-                                        if sender.send(signal.clone()).is_ok() {
-                                            delivered = true;
-                                        } else {
-                                            log::warn!("[control] gRPC channel closed for {robot_id}, retrying");
-                                        }
+                                    // 다른 검사에서 거절된 명령은 토큰을 쓰지 않도록 전송 직전에 확인한다.
+                                    if let Err(scope) = self.check_rate_limit(&robot_id, &mut rate_limiter, kind).await {
+                                        log::warn!("[control] rate limited {kind:?} from session {session_id} for {robot_id}: {scope}");
+                                        self.metrics.record_rate_limited();
+                                        let _ = send_control_error_with_code(
+                                            &mut ws_sink,
+                                            &robot_id,
+                                            request_id.as_deref(),
+                                            ControlErrorCode::RateLimited,
+                                            scope.to_string(),
+                                        )
+                                        .await;
+                                        continue;
                                     }
 
-                                    if !delivered {
-                                        if let Err(e) = self.init_signaling(&robot_id).await {
-                                            log::warn!("[control] retry init signaling failed for {robot_id}: {e}");
-                                        } else if let Ok(sender) = self.grpc.signal_sender().await {
-                                            if sender.send(signal.clone()).is_ok() {
-                                                delivered = true;
-                                                log::info!("[control] resent signal after reconnect for {robot_id}");
-                                            }
-                                        }
-                                    }
+                                    // robot 응답이 전송 직후 와도 놓치지 않도록 보내기 전에 등록한다.
+                                    let result_rx = if ack_mode == AckMode::Robot {
+                                        let (tx, rx) = oneshot::channel();
+                                        self.sessions.write().await.register_command(command_id.clone(), tx);
+                                        Some(rx)
+                                    } else {
+                                        None
+                                    };
 
-                                    if !delivered {
+                                    if let Err(e) = self.forward_control_signal(&robot_id, signal).await {
+                                        if result_rx.is_some() {
+                                            self.sessions.write().await.cancel_command(&command_id);
+                                        }
+                                        if e == QueueError::Full {
+                                            log::warn!("[control] gRPC outbound queue full for {robot_id}, rejecting command_id={command_id}");
+                                            let _ = send_control_error_with_code(
+                                                &mut ws_sink,
+                                                &robot_id,
+                                                request_id.as_deref(),
+                                                ControlErrorCode::QueueFull,
+                                                "gateway outbound queue is full, retry later",
+                                            )
+                                            .await;
+                                            continue;
+                                        }
                                         let _ = send_control_error(
                                             &mut ws_sink,
+                                            &robot_id,
+                                            request_id.as_deref(),
                                             "signaling sender unavailable",
                                         )
                                         .await;
-                                        eprintln!("[control] failed to send over gRPC channel for {robot_id}");
+                                        log::error!("[control] failed to send over gRPC channel for {robot_id}");
+                                        exit = ControlExit::SignalingUnavailable;
                                         break;
                                     }
 
-                                    log::info!("[control] sent to gRPC channel for {robot_id}");
-                                    if let Err(e) = send_control_ack(&mut ws_sink, "command accepted").await {
-                                        eprintln!("[control] failed to send ack to client for {robot_id}: {e}");
+                                    log::info!("[control] sent to gRPC channel for {robot_id} command_id={command_id}");
+                                    accept_seq(&mut last_seq, seq);
+                                    self.renew_lease(&robot_id, session_id).await;
+                                    // 거절된 명령이 watchdog을 시작하지 않도록, 전송된 move/velocity만 deadline을 시작(연장)한다.
+                                    if let Some(window) = heartbeat_window
+                                        && kind.refreshes_heartbeat()
+                                    {
+                                        heartbeat_deadline = Some(Instant::now() + window);
+                                    }
+                                    audit(identity, &robot_id, format_args!("{kind:?} command_id={command_id}"), "sent");
+                                    // 새 motion/stop 명령은 이전 시간 제한 move의 STOP 예약을 대체한다.
+                                    if let Some(duration) = timed_move {
+                                        self.schedule_timed_stop(&robot_id, session_id, duration).await;
+                                    } else if kind.cancels_timed_stop()
+                                        && self.sessions.write().await.cancel_motion_stop(&robot_id)
+                                    {
+                                        log::info!("[control] timed move STOP cancelled by {kind:?} for {robot_id}");
+                                    }
+                                    if kind == ControlRequestType::ResetEstop
+                                        && self.sessions.write().await.reset_estop(&robot_id)
+                                    {
+                                        log::warn!("[control] emergency stop latch reset for {robot_id}");
+                                    }
+                                    match result_rx {
+                                        Some(rx) => self.wait_command_result(command_id, rx, request_id, outcome_tx.clone()),
+                                        None => {
+                                            if let Err(e) = send_control_ack(&mut ws_sink, &robot_id, request_id.as_deref(), "command accepted").await {
+                                                log::warn!("[control] failed to send ack to client for {robot_id}: {e}");
+                                            }
+                                        }
                                     }
                                 }
                                 Err(e) => {
-                                    eprintln!("[control] signal conversion error for {robot_id}: {e}");
+                                    log::warn!("[control] signal conversion error for {robot_id}: {e}");
                                     if let Err(err) = send_control_error(
                                         &mut ws_sink,
+                                        &robot_id,
+                                        request_id.as_deref(),
                                         format!("invalid control command: {e}"),
                                     )
                                     .await
                                     {
-                                        eprintln!("[control] failed to send error to client for {robot_id}: {err}");
+                                        log::warn!("[control] failed to send error to client for {robot_id}: {err}");
                                     }
                                 }
                             }
                         }
                         Ok(Message::Close(frame)) => {
                             log::info!("[control] close frame for {robot_id}: {:?}", frame);
+                            exit = ControlExit::Closed;
                             break;
                         }
+                        Ok(Message::Pong(_)) => {
+                            last_pong = Instant::now();
+                        }
                         Ok(other) => {
                             log::info!("[control] ignore ws message for {robot_id}: {:?}", other);
                         }
                         Err(e) => {
-                            eprintln!("[control] websocket error for {robot_id}: {e}");
+                            log::warn!("[control] websocket error for {robot_id}: {e}");
                             break;
                         }
                     }
@@ -329,17 +1026,50 @@ impl WebSocketHandler {
             }
         }
 
-        println!("[control] loop finished for {robot_id}");
-        // WS 종료 시 gRPC signaling 스트림도 정리
-        self.grpc.close_signal_stream().await;
+        log::info!("[control] loop finished for {robot_id}");
 
-        Ok(())
+        Ok(exit)
     }
 }
 
-fn parse_control_request(text: &str, robot_id: &str) -> anyhow::Result<WsSignalMessage> {
-    let req: ControlRequest = serde_json::from_str(text)?;
+/// robot-level ack 명령의 최종 결과
+enum CommandOutcome {
+    Completed {
+        result: CommandResult,
+        request_id: Option<String>,
+    },
+    TimedOut {
+        command_id: String,
+        request_id: Option<String>,
+    },
+}
+
+/// control 세션이 끝난 이유 (dead-man STOP 판단용)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ControlExit {
+    /// 클라이언트가 close frame을 보내 정상 종료
+    Closed,
+    /// 소켓 에러 또는 close frame 없이 스트림이 끊김
+    SocketError,
+    /// ping에 대한 pong이 제 시간에 오지 않음
+    PongTimeout,
+    /// gateway 프로세스 종료
+    Shutdown,
+    /// gRPC signaling 전달 실패로 세션을 끊음
+    SignalingUnavailable,
+}
+
+impl ControlExit {
+    fn is_abnormal(self) -> bool {
+        self != ControlExit::Closed
+    }
+}
 
+fn parse_control_request(
+    req: ControlRequest,
+    robot_id: &str,
+    command_id: String,
+) -> anyhow::Result<WsSignalMessage> {
     let payload = match req.payload {
         Value::Object(map) => map,
         Value::Null => Map::new(),
@@ -352,20 +1082,26 @@ fn parse_control_request(text: &str, robot_id: &str) -> anyhow::Result<WsSignalM
 
     let (command, payload) = match req.kind {
         ControlRequestType::Move => {
-            let direction = payload
+            let direction: Direction = payload
                 .get("direction")
                 .and_then(Value::as_str)
-                .ok_or_else(|| anyhow!("direction is required for move command"))?;
-            let speed = payload
-                .get("speed")
-                .and_then(Value::as_f64)
-                .unwrap_or(1.0);
+                .ok_or_else(|| anyhow!("direction is required for move command"))?
+                .parse()?;
+            let speed = optional_number(&payload, "speed", 1.0)?;
+            let duration_ms = match payload.get("duration_ms") {
+                None | Some(Value::Null) => None,
+                Some(v) => Some(
+                    v.as_u64()
+                        .ok_or_else(|| anyhow!("duration_ms must be a non-negative integer"))?,
+                ),
+            };
 
             (
                 CommandType::Move,
                 Some(ControlPayload::Move {
-                    direction: direction.to_string(),
+                    direction: direction.as_str().to_string(),
                     speed,
+                    duration_ms,
                 }),
             )
         }
@@ -382,7 +1118,68 @@ fn parse_control_request(text: &str, robot_id: &str) -> anyhow::Result<WsSignalM
                 Some(ControlPayload::SetSpeed { speed }),
             )
         }
+        ControlRequestType::Velocity => (
+            CommandType::Velocity,
+            Some(ControlPayload::Velocity {
+                linear_x: optional_number(&payload, "linear_x", 0.0)?,
+                linear_y: optional_number(&payload, "linear_y", 0.0)?,
+                angular_z: optional_number(&payload, "angular_z", 0.0)?,
+            }),
+        ),
+        ControlRequestType::NavigateTo => {
+            let coordinate = |key: &str| {
+                payload
+                    .get(key)
+                    .and_then(Value::as_f64)
+                    .ok_or_else(|| anyhow!("{key} is required for navigate_to command"))
+            };
+            let (x, y) = (coordinate("x")?, coordinate("y")?);
+            let frame_id = match payload.get("frame_id") {
+                None | Some(Value::Null) => default_frame_id(),
+                Some(v) => v
+                    .as_str()
+                    .ok_or_else(|| anyhow!("frame_id must be a string for navigate_to command"))?
+                    .to_string(),
+            };
+
+            (
+                CommandType::NavigateTo,
+                Some(ControlPayload::NavigateTo {
+                    x,
+                    y,
+                    theta: optional_number(&payload, "theta", 0.0)?,
+                    frame_id,
+                    tolerance: optional_number(&payload, "tolerance", 0.0)?,
+                }),
+            )
+        }
+        ControlRequestType::Custom => {
+            let (name, params) = named_command(&payload)?;
+            (CommandType::Custom, Some(ControlPayload::Custom { name, params }))
+        }
+        ControlRequestType::Vendor => {
+            let (name, params) = named_command(&payload)?;
+            (
+                CommandType::Vendor,
+                Some(ControlPayload::Vendor {
+                    model: String::new(),
+                    name,
+                    params,
+                }),
+            )
+        }
         ControlRequestType::Dock => (CommandType::Dock, None),
+        ControlRequestType::Undock => (CommandType::Undock, None),
+        ControlRequestType::Pause => (CommandType::Pause, None),
+        ControlRequestType::Resume => (CommandType::Resume, None),
+        ControlRequestType::Cancel => (CommandType::Cancel, None),
+        ControlRequestType::ResetEstop => (CommandType::ResetEstop, None),
+        ControlRequestType::Heartbeat
+        | ControlRequestType::RequestControl
+        | ControlRequestType::ReleaseControl
+        | ControlRequestType::HandoverControl => {
+            return Err(anyhow!("{:?} is handled by the gateway and is not a robot command", req.kind))
+        }
         ControlRequestType::PathFollow => {
             let path_id = payload
                 .get("path_id")
@@ -401,34 +1198,358 @@ fn parse_control_request(text: &str, robot_id: &str) -> anyhow::Result<WsSignalM
     Ok(WsSignalMessage::ControlCommand {
         robot_id: robot_id.to_string(),
         command,
+        command_id: Some(command_id),
+        sequence: req.seq,
         payload,
     })
 }
 
+/// custom/vendor 명령의 name과 params (params는 생략 가능, object만 허용)
+fn named_command(payload: &Map<String, Value>) -> anyhow::Result<(String, Map<String, Value>)> {
+    let name = payload
+        .get("name")
+        .and_then(Value::as_str)
+        .ok_or_else(|| anyhow!("name is required for custom/vendor command"))?
+        .to_string();
+    let params = match payload.get("params") {
+        None | Some(Value::Null) => Map::new(),
+        Some(Value::Object(params)) => params.clone(),
+        Some(other) => return Err(anyhow!("command params must be an object, got: {other}")),
+    };
+    Ok((name, params))
+}
+
+/// 선택 숫자 필드 (move speed, velocity 성분, navigate_to의 theta/tolerance):
+/// 생략(또는 null)하면 `default`, 숫자가 아니면 에러
+fn optional_number(payload: &Map<String, Value>, key: &str, default: f64) -> anyhow::Result<f64> {
+    match payload.get(key) {
+        None | Some(Value::Null) => Ok(default),
+        Some(v) => v
+            .as_f64()
+            .ok_or_else(|| anyhow!("{key} must be a number")),
+    }
+}
+
+/// screen 채널에서 클라이언트가 보낼 수 있는 메시지면 대상 robot_id를 돌려준다.
+fn screen_signal_robot_id(msg: &WsSignalMessage) -> Option<&str> {
+    match msg {
+        WsSignalMessage::ScreenRequest { robot_id }
+        | WsSignalMessage::ClientAnswer { robot_id, .. }
+        | WsSignalMessage::ClientIce { robot_id, .. } => Some(robot_id),
+        _ => None,
+    }
+}
+
+/// 권한 없는 채널 접속을 policy violation(1008)으로 닫는다.
+async fn close_forbidden(mut ws_stream: WebSocketStream<TcpStream>, reason: &'static str) -> anyhow::Result<()> {
+    let frame = CloseFrame {
+        code: CloseCode::Policy,
+        reason: reason.into(),
+    };
+    if let Err(e) = ws_stream.close(Some(frame)).await {
+        log::warn!("[auth] failed to close forbidden channel: {e}");
+    }
+    Ok(())
+}
+
+/// handshake 인증 실패 응답 (WebSocket upgrade 전)
+fn unauthorized_response() -> ErrorResponse {
+    let mut resp = ErrorResponse::new(Some("unauthorized".to_string()));
+    *resp.status_mut() = StatusCode::UNAUTHORIZED;
+    resp.headers_mut()
+        .insert(WWW_AUTHENTICATE, HeaderValue::from_static("Bearer"));
+    resp
+}
+
 fn extract_robot_id(path: &str, prefix: &str) -> Option<String> {
     path.strip_prefix(prefix)
         .map(|rest| rest.trim_end_matches('/').to_string())
 }
 
-async fn send_control_ack(ws_sink: &mut WsSink, message: impl Into<String>) -> anyhow::Result<()> {
-    let payload = json!({
-        "type": "control_ack",
-        "message": message.into(),
-    });
+/// seq/timestamp_ms로 순서가 뒤바뀌었거나 너무 늦게 도착한 요청을 거른다.
+/// e_stop은 늦게 오더라도 항상 통과시킨다.
+/// `last_seq`는 바꾸지 않는다. 요청이 모든 검사를 통과해 처리된 뒤 `accept_seq`로 갱신한다.
+fn check_command_freshness(
+    req: &ControlRequest,
+    last_seq: Option<u64>,
+    max_age_ms: u64,
+) -> Result<(), (ControlErrorCode, String)> {
+    if matches!(req.kind, ControlRequestType::EStop) {
+        return Ok(());
+    }
+
+    if let Some(seq) = req.seq
+        && let Some(last) = last_seq
+        && seq <= last
+    {
+        return Err((
+            ControlErrorCode::OutOfOrder,
+            format!("seq {seq} is not greater than last seq {last}"),
+        ));
+    }
+
+    if max_age_ms > 0
+        && let Some(sent_at) = req.timestamp_ms
+    {
+        let now_ms = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_millis() as u64)
+            .unwrap_or_default();
+        let age_ms = now_ms.saturating_sub(sent_at);
+        if age_ms > max_age_ms {
+            return Err((
+                ControlErrorCode::Expired,
+                format!("command is {age_ms}ms old (max {max_age_ms}ms)"),
+            ));
+        }
+    }
 
-    ws_sink.send(Message::Text(payload.to_string().into())).await?;
     Ok(())
 }
 
-async fn send_control_error(
+/// 시간 제한 move의 STOP timer. `duration`이 지났을 때 예약이 아직 유효하면 true (STOP을 보낸다),
+/// 그 전에 새 motion/stop 명령이나 세션 종료로 취소되면 false.
+async fn wait_motion_stop(
+    sessions: &SharedSessions,
+    robot_id: &str,
+    timer_id: u64,
+    duration: Duration,
+    cancelled: oneshot::Receiver<()>,
+) -> bool {
+    tokio::select! {
+        _ = time::sleep(duration) => sessions.write().await.take_motion_stop(robot_id, timer_id),
+        _ = cancelled => false,
+    }
+}
+
+/// 처리된 요청의 seq를 기록한다. (거절된 요청의 seq는 같은 값으로 다시 보낼 수 있다)
+/// 늦게 온 e_stop의 seq가 더 작을 수 있으므로 큰 값을 유지한다.
+fn accept_seq(last_seq: &mut Option<u64>, seq: Option<u64>) {
+    if let Some(seq) = seq {
+        *last_seq = Some(last_seq.map_or(seq, |last| last.max(seq)));
+    }
+}
+
+/// JSON 파싱에 실패한 요청에서도 request_id만은 꺼내 본다.
+fn extract_request_id(text: &str) -> Option<String> {
+    serde_json::from_str::<Value>(text)
+        .ok()?
+        .get("request_id")?
+        .as_str()
+        .map(str::to_string)
+}
+
+async fn send_control_response(ws_sink: &mut WsSink, response: &WsControlResponse) -> anyhow::Result<()> {
+    let payload = serde_json::to_string(response)?;
+    ws_sink.send(Message::Text(payload.into())).await?;
+    Ok(())
+}
+
+async fn send_control_ack(
     ws_sink: &mut WsSink,
+    robot_id: &str,
+    request_id: Option<&str>,
     message: impl Into<String>,
+) -> anyhow::Result<()> {
+    let response = WsControlResponse::Ack {
+        robot_id: robot_id.to_string(),
+        request_id: request_id.map(str::to_string),
+        message: message.into(),
+    };
+
+    send_control_response(ws_sink, &response).await
+}
+
+async fn send_lease_notice(
+    ws_sink: &mut WsSink,
+    robot_id: &str,
+    session_id: SessionId,
+    notice: &LeaseNotice,
 ) -> anyhow::Result<()> {
     let payload = json!({
-        "type": "control_error",
-        "message": message.into(),
+        "type": "control_lease",
+        "robot_id": robot_id,
+        "session_id": session_id,
+        "holder": notice.holder,
+        "reason": notice.reason,
     });
 
     ws_sink.send(Message::Text(payload.to_string().into())).await?;
     Ok(())
 }
+
+async fn send_control_error(
+    ws_sink: &mut WsSink,
+    robot_id: &str,
+    request_id: Option<&str>,
+    message: impl Into<String>,
+) -> anyhow::Result<()> {
+    let response = WsControlResponse::Error {
+        robot_id: robot_id.to_string(),
+        request_id: request_id.map(str::to_string),
+        code: None,
+        message: message.into(),
+        details: None,
+    };
+
+    send_control_response(ws_sink, &response).await
+}
+
+async fn send_control_error_with_code(
+    ws_sink: &mut WsSink,
+    robot_id: &str,
+    request_id: Option<&str>,
+    code: ControlErrorCode,
+    message: impl Into<String>,
+) -> anyhow::Result<()> {
+    send_control_error_with_details(ws_sink, robot_id, request_id, code, message, None).await
+}
+
+async fn send_control_error_with_details(
+    ws_sink: &mut WsSink,
+    robot_id: &str,
+    request_id: Option<&str>,
+    code: ControlErrorCode,
+    message: impl Into<String>,
+    details: Option<Value>,
+) -> anyhow::Result<()> {
+    let response = WsControlResponse::Error {
+        robot_id: robot_id.to_string(),
+        request_id: request_id.map(str::to_string),
+        code: Some(code),
+        message: message.into(),
+        details,
+    };
+
+    send_control_response(ws_sink, &response).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn request(kind: &str, seq: Option<u64>, timestamp_ms: Option<u64>) -> ControlRequest {
+        serde_json::from_value(json!({ "type": kind, "seq": seq, "timestamp_ms": timestamp_ms })).unwrap()
+    }
+
+    fn now_ms() -> u64 {
+        SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_millis() as u64
+    }
+
+    fn code(result: Result<(), (ControlErrorCode, String)>) -> Option<ControlErrorCode> {
+        result.err().map(|(code, _)| code)
+    }
+
+    #[test]
+    fn seq_must_increase() {
+        let mut last_seq = None;
+        assert!(check_command_freshness(&request("move", Some(5), None), last_seq, 0).is_ok());
+        accept_seq(&mut last_seq, Some(5));
+
+        assert_eq!(code(check_command_freshness(&request("move", Some(4), None), last_seq, 0)), Some(ControlErrorCode::OutOfOrder));
+        assert_eq!(code(check_command_freshness(&request("move", Some(5), None), last_seq, 0)), Some(ControlErrorCode::OutOfOrder));
+        assert!(check_command_freshness(&request("move", Some(6), None), last_seq, 0).is_ok());
+        // seq 없는 요청은 순서 검사를 하지 않는다.
+        assert!(check_command_freshness(&request("move", None, None), last_seq, 0).is_ok());
+    }
+
+    #[test]
+    fn rejected_seq_can_be_resent() {
+        // 검사만으로는 seq를 쓰지 않는다. 처리된 요청만 accept_seq로 기록된다.
+        let last_seq = Some(5);
+        let retry = request("move", Some(6), None);
+        assert!(check_command_freshness(&retry, last_seq, 0).is_ok());
+        assert!(check_command_freshness(&retry, last_seq, 0).is_ok());
+    }
+
+    #[test]
+    fn late_command_is_expired() {
+        let late = request("move", Some(1), Some(now_ms() - 5_000));
+        assert_eq!(code(check_command_freshness(&late, None, 1_000)), Some(ControlErrorCode::Expired));
+
+        let fresh = request("move", Some(1), Some(now_ms()));
+        assert!(check_command_freshness(&fresh, None, 1_000).is_ok());
+    }
+
+    #[test]
+    fn zero_max_age_disables_the_age_check() {
+        let ancient = request("move", None, Some(1));
+        assert!(check_command_freshness(&ancient, None, 0).is_ok());
+    }
+
+    #[test]
+    fn e_stop_bypasses_seq_and_age_checks() {
+        let mut last_seq = Some(10);
+        let late_estop = request("e_stop", Some(3), Some(now_ms() - 60_000));
+        assert!(check_command_freshness(&late_estop, last_seq, 1_000).is_ok());
+
+        // 늦게 온 e_stop의 seq가 더 작아도 last_seq는 뒤로 가지 않는다.
+        accept_seq(&mut last_seq, Some(3));
+        assert_eq!(last_seq, Some(10));
+        accept_seq(&mut last_seq, Some(12));
+        assert_eq!(last_seq, Some(12));
+    }
+
+    fn sessions() -> SharedSessions {
+        Arc::new(tokio::sync::RwLock::new(crate::session::manager::SessionManager::new(
+            Duration::from_secs(30),
+            1,
+            None,
+        )))
+    }
+
+    const JOG: Duration = Duration::from_millis(500);
+
+    #[tokio::test(start_paused = true)]
+    async fn timed_stop_fires_when_the_timer_ends() {
+        let sessions = sessions();
+        let (timer_id, cancelled) = sessions.write().await.schedule_motion_stop("robot-01", 1);
+
+        let started = Instant::now();
+        assert!(wait_motion_stop(&sessions, "robot-01", timer_id, JOG, cancelled).await);
+        assert!(started.elapsed() >= JOG);
+        // 한 번 보낸 STOP은 다시 취소/발송되지 않는다.
+        assert!(!sessions.write().await.cancel_motion_stop("robot-01"));
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn newer_move_replaces_the_previous_timer() {
+        let sessions = sessions();
+        let (first, first_cancelled) = sessions.write().await.schedule_motion_stop("robot-01", 1);
+        let (second, second_cancelled) = sessions.write().await.schedule_motion_stop("robot-01", 1);
+
+        // 이전 timer는 기다리지 않고 바로 취소된다.
+        let started = Instant::now();
+        assert!(!wait_motion_stop(&sessions, "robot-01", first, JOG, first_cancelled).await);
+        assert_eq!(started.elapsed(), Duration::ZERO);
+        assert!(wait_motion_stop(&sessions, "robot-01", second, JOG, second_cancelled).await);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn manual_stop_or_estop_cancels_the_timer() {
+        for kind in [ControlRequestType::Stop, ControlRequestType::EStop, ControlRequestType::Velocity] {
+            assert!(kind.cancels_timed_stop(), "{kind:?}");
+            let sessions = sessions();
+            let (timer_id, cancelled) = sessions.write().await.schedule_motion_stop("robot-01", 1);
+            assert!(sessions.write().await.cancel_motion_stop("robot-01"));
+            assert!(!wait_motion_stop(&sessions, "robot-01", timer_id, JOG, cancelled).await);
+        }
+        for kind in [ControlRequestType::Heartbeat, ControlRequestType::Pause, ControlRequestType::ResetEstop] {
+            assert!(!kind.cancels_timed_stop(), "{kind:?}");
+        }
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn session_end_cancels_only_its_own_timer() {
+        let sessions = sessions();
+        let (timer_id, cancelled) = sessions.write().await.schedule_motion_stop("robot-01", 1);
+        let (other_id, other_cancelled) = sessions.write().await.schedule_motion_stop("robot-02", 2);
+
+        assert!(!sessions.write().await.cancel_session_motion_stop("robot-01", 2));
+        assert!(sessions.write().await.cancel_session_motion_stop("robot-01", 1));
+        assert!(!wait_motion_stop(&sessions, "robot-01", timer_id, JOG, cancelled).await);
+
+        // 다른 세션이 예약한 timer는 그대로 돈다.
+        assert!(wait_motion_stop(&sessions, "robot-02", other_id, JOG, other_cancelled).await);
+    }
+}
@@ -0,0 +1,390 @@
+use std::collections::HashMap;
+use std::fmt;
+use tokio::sync::mpsc;
+use tokio::time::{Duration, Instant};
+
+use crate::domain::control::ControlErrorCode;
+use crate::session::manager::SessionId;
+
+/// lease 상태가 바뀔 때 control 세션들에게 보내는 알림
+#[derive(Debug, Clone)]
+pub struct LeaseNotice {
+    pub holder: Option<SessionId>,
+    pub reason: &'static str,
+}
+
+pub type LeaseNotifier = mpsc::UnboundedSender<LeaseNotice>;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LeaseError {
+    /// 다른 operator가 lease를 보유 중
+    HeldByOther { holder: SessionId },
+    /// 요청한 세션이 lease를 보유하고 있지 않음 (만료 포함)
+    NotHolder,
+    /// handover 대상이 같은 robot의 control 세션이 아님
+    UnknownSession(SessionId),
+}
+
+impl fmt::Display for LeaseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LeaseError::HeldByOther { holder } => {
+                write!(f, "control lease is held by session {holder}")
+            }
+            LeaseError::NotHolder => {
+                write!(f, "control lease not held (send request_control first)")
+            }
+            LeaseError::UnknownSession(id) => {
+                write!(f, "session {id} is not a control session of this robot")
+            }
+        }
+    }
+}
+
+impl std::error::Error for LeaseError {}
+
+impl LeaseError {
+    /// control_error로 보낼 code
+    pub fn code(&self) -> ControlErrorCode {
+        match self {
+            LeaseError::HeldByOther { .. } => ControlErrorCode::LeaseHeld,
+            LeaseError::NotHolder => ControlErrorCode::LeaseRequired,
+            LeaseError::UnknownSession(_) => ControlErrorCode::InvalidPayload,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Lease {
+    holder: SessionId,
+    expires_at: Instant,
+    // lease_expired 알림을 이미 보냈는지
+    expired: bool,
+}
+
+/// robot별 독점 제어권(lease) 관리.
+/// lease 보유자만 제어 명령을 보낼 수 있고, 나머지 control 세션은 read-only로 붙어 있는다.
+/// 보유자의 heartbeat나 전송된 명령마다 lease가 갱신되며, ttl 동안 아무것도 없으면 만료되고
+/// 보유자를 포함한 control 세션들에게 lease_expired를 알린다.
+pub struct LeaseManager {
+    ttl: Duration,
+    leases: HashMap<String, Lease>,
+    // robot_id -> (session_id -> 알림 sender), 연결된 control 세션 목록
+    operators: HashMap<String, HashMap<SessionId, LeaseNotifier>>,
+}
+
+impl LeaseManager {
+    pub fn new(ttl: Duration) -> Self {
+        Self {
+            ttl,
+            leases: HashMap::new(),
+            operators: HashMap::new(),
+        }
+    }
+
+    pub fn register(&mut self, robot_id: &str, session_id: SessionId, notifier: LeaseNotifier) {
+        self.operators
+            .entry(robot_id.to_string())
+            .or_default()
+            .insert(session_id, notifier);
+    }
+
+    /// control 세션 종료 시 호출. 보유 중이던 lease는 해제되고 나머지 세션에 알린다.
+    pub fn unregister(&mut self, robot_id: &str, session_id: SessionId) {
+        if let Some(ops) = self.operators.get_mut(robot_id) {
+            ops.remove(&session_id);
+            if ops.is_empty() {
+                self.operators.remove(robot_id);
+            }
+        }
+
+        if self.is_holder(robot_id, session_id) {
+            self.leases.remove(robot_id);
+            self.notify(robot_id, None, "holder_disconnected");
+        }
+    }
+
+    /// 현재 보유자 (만료된 lease는 보유자 없음으로 본다)
+    pub fn holder(&self, robot_id: &str, now: Instant) -> Option<SessionId> {
+        self.leases
+            .get(robot_id)
+            .filter(|lease| lease.expires_at > now)
+            .map(|lease| lease.holder)
+    }
+
+    /// 만료 여부와 무관하게 마지막으로 lease를 가졌던 세션인지
+    pub fn is_holder(&self, robot_id: &str, session_id: SessionId) -> bool {
+        self.leases
+            .get(robot_id)
+            .is_some_and(|lease| lease.holder == session_id)
+    }
+
+    /// 만료된 lease를 처음 발견하면 lease_expired를 알린다. 보유자 기록은 dead-man STOP 판단용으로 남긴다.
+    pub fn expire(&mut self, robot_id: &str, now: Instant) -> bool {
+        let Some(lease) = self.leases.get_mut(robot_id) else {
+            return false;
+        };
+        if lease.expired || lease.expires_at > now {
+            return false;
+        }
+        lease.expired = true;
+        self.notify(robot_id, None, "lease_expired");
+        true
+    }
+
+    /// 보유자의 heartbeat나 전송된 명령이 있을 때 ttl을 다시 시작한다. 만료된 lease는 갱신하지 않는다.
+    pub fn renew(&mut self, robot_id: &str, session_id: SessionId, now: Instant) -> bool {
+        self.expire(robot_id, now);
+        if self.holder(robot_id, now) != Some(session_id) {
+            return false;
+        }
+        self.grant(robot_id, session_id, now);
+        true
+    }
+
+    /// lease 요청. 비어 있거나 만료됐거나 이미 보유 중이면 부여(갱신)한다.
+    pub fn request(&mut self, robot_id: &str, session_id: SessionId, now: Instant) -> Result<(), LeaseError> {
+        self.expire(robot_id, now);
+        match self.holder(robot_id, now) {
+            Some(holder) if holder != session_id => Err(LeaseError::HeldByOther { holder }),
+            Some(_) => {
+                self.grant(robot_id, session_id, now);
+                Ok(())
+            }
+            None => {
+                self.grant(robot_id, session_id, now);
+                self.notify(robot_id, Some(session_id), "granted");
+                Ok(())
+            }
+        }
+    }
+
+    pub fn release(&mut self, robot_id: &str, session_id: SessionId, now: Instant) -> Result<(), LeaseError> {
+        self.expire(robot_id, now);
+        if self.holder(robot_id, now) != Some(session_id) {
+            return Err(LeaseError::NotHolder);
+        }
+        self.leases.remove(robot_id);
+        self.notify(robot_id, None, "released");
+        Ok(())
+    }
+
+    /// 보유자가 같은 robot의 다른 control 세션에게 lease를 넘긴다.
+    pub fn handover(
+        &mut self,
+        robot_id: &str,
+        from: SessionId,
+        to: SessionId,
+        now: Instant,
+    ) -> Result<(), LeaseError> {
+        self.expire(robot_id, now);
+        if self.holder(robot_id, now) != Some(from) {
+            return Err(LeaseError::NotHolder);
+        }
+        let known = self
+            .operators
+            .get(robot_id)
+            .is_some_and(|ops| ops.contains_key(&to));
+        if !known {
+            return Err(LeaseError::UnknownSession(to));
+        }
+
+        self.grant(robot_id, to, now);
+        self.notify(robot_id, Some(to), "handover");
+        Ok(())
+    }
+
+    /// 제어 명령 전 확인. 갱신은 명령이 전송된 뒤 `renew`로 한다.
+    pub fn check(&mut self, robot_id: &str, session_id: SessionId, now: Instant) -> Result<(), LeaseError> {
+        self.expire(robot_id, now);
+        match self.holder(robot_id, now) {
+            Some(holder) if holder == session_id => Ok(()),
+            Some(holder) => Err(LeaseError::HeldByOther { holder }),
+            None => Err(LeaseError::NotHolder),
+        }
+    }
+
+    fn grant(&mut self, robot_id: &str, session_id: SessionId, now: Instant) {
+        self.leases.insert(
+            robot_id.to_string(),
+            Lease {
+                holder: session_id,
+                expires_at: now + self.ttl,
+                expired: false,
+            },
+        );
+    }
+
+    fn notify(&self, robot_id: &str, holder: Option<SessionId>, reason: &'static str) {
+        if let Some(ops) = self.operators.get(robot_id) {
+            for tx in ops.values() {
+                let _ = tx.send(LeaseNotice { holder, reason });
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TTL: Duration = Duration::from_secs(30);
+
+    /// robot-01에 control 세션들을 붙이고 각 세션의 알림 receiver를 돌려준다.
+    fn manager(sessions: &[SessionId]) -> (LeaseManager, Vec<mpsc::UnboundedReceiver<LeaseNotice>>) {
+        let mut leases = LeaseManager::new(TTL);
+        let receivers = sessions
+            .iter()
+            .map(|&session_id| {
+                let (tx, rx) = mpsc::unbounded_channel();
+                leases.register("robot-01", session_id, tx);
+                rx
+            })
+            .collect();
+        (leases, receivers)
+    }
+
+    fn notices(rx: &mut mpsc::UnboundedReceiver<LeaseNotice>) -> Vec<(Option<SessionId>, &'static str)> {
+        std::iter::from_fn(|| rx.try_recv().ok())
+            .map(|notice| (notice.holder, notice.reason))
+            .collect()
+    }
+
+    #[test]
+    fn request_grants_a_free_lease_and_rejects_others() {
+        let (mut leases, mut rx) = manager(&[1, 2]);
+        let now = Instant::now();
+
+        leases.request("robot-01", 1, now).unwrap();
+        assert_eq!(leases.holder("robot-01", now), Some(1));
+        assert_eq!(notices(&mut rx[1]), [(Some(1), "granted")]);
+
+        assert_eq!(leases.request("robot-01", 2, now), Err(LeaseError::HeldByOther { holder: 1 }));
+        assert_eq!(leases.check("robot-01", 2, now), Err(LeaseError::HeldByOther { holder: 1 }));
+
+        // 보유자의 재요청은 갱신만 하고 다시 알리지 않는다.
+        leases.request("robot-01", 1, now).unwrap();
+        assert_eq!(notices(&mut rx[0]), [(Some(1), "granted")]);
+        assert!(notices(&mut rx[1]).is_empty());
+
+        // 다른 robot의 lease는 따로 관리된다.
+        leases.request("robot-02", 2, now).unwrap();
+        assert_eq!(leases.holder("robot-02", now), Some(2));
+    }
+
+    #[test]
+    fn release_frees_the_lease_for_the_holder_only() {
+        let (mut leases, mut rx) = manager(&[1, 2]);
+        let now = Instant::now();
+        assert_eq!(leases.release("robot-01", 1, now), Err(LeaseError::NotHolder));
+
+        leases.request("robot-01", 1, now).unwrap();
+        assert_eq!(leases.release("robot-01", 2, now), Err(LeaseError::NotHolder));
+        leases.release("robot-01", 1, now).unwrap();
+        assert_eq!(leases.holder("robot-01", now), None);
+        assert_eq!(notices(&mut rx[1]), [(Some(1), "granted"), (None, "released")]);
+
+        assert_eq!(leases.check("robot-01", 1, now), Err(LeaseError::NotHolder));
+        leases.request("robot-01", 2, now).unwrap();
+    }
+
+    #[test]
+    fn handover_moves_the_lease_to_another_control_session() {
+        let (mut leases, mut rx) = manager(&[1, 2]);
+        let now = Instant::now();
+        leases.request("robot-01", 1, now).unwrap();
+
+        assert_eq!(leases.handover("robot-01", 2, 1, now), Err(LeaseError::NotHolder));
+        assert_eq!(leases.handover("robot-01", 1, 3, now), Err(LeaseError::UnknownSession(3)));
+        assert_eq!(leases.holder("robot-01", now), Some(1));
+
+        leases.handover("robot-01", 1, 2, now).unwrap();
+        assert_eq!(leases.holder("robot-01", now), Some(2));
+        assert_eq!(notices(&mut rx[0]), [(Some(1), "granted"), (Some(2), "handover")]);
+        assert_eq!(leases.check("robot-01", 1, now), Err(LeaseError::HeldByOther { holder: 2 }));
+        leases.check("robot-01", 2, now).unwrap();
+    }
+
+    #[test]
+    fn lease_expires_after_ttl_without_renewal() {
+        let (mut leases, mut rx) = manager(&[1, 2]);
+        let start = Instant::now();
+        leases.request("robot-01", 1, start).unwrap();
+        notices(&mut rx[0]);
+        notices(&mut rx[1]);
+
+        // check만으로는 갱신되지 않고, heartbeat/전송된 명령(renew)마다 ttl이 다시 시작된다.
+        let renewed = start + TTL - Duration::from_secs(1);
+        leases.check("robot-01", 1, renewed).unwrap();
+        assert!(!leases.renew("robot-01", 2, renewed));
+        assert!(leases.renew("robot-01", 1, renewed));
+        assert_eq!(leases.holder("robot-01", start + TTL), Some(1));
+
+        let expired = renewed + TTL;
+        assert_eq!(leases.holder("robot-01", expired), None);
+        assert_eq!(leases.check("robot-01", 1, expired), Err(LeaseError::NotHolder));
+        // 만료돼도 마지막 보유자는 기록된다 (dead-man STOP 판단용)
+        assert!(leases.is_holder("robot-01", 1));
+        // 만료된 lease는 renew로 되살리지 않는다.
+        assert!(!leases.renew("robot-01", 1, expired));
+
+        leases.request("robot-01", 2, expired).unwrap();
+        assert_eq!(leases.check("robot-01", 1, expired), Err(LeaseError::HeldByOther { holder: 2 }));
+    }
+
+    #[test]
+    fn expiry_is_announced_once_to_every_control_session() {
+        let (mut leases, mut rx) = manager(&[1, 2]);
+        let start = Instant::now();
+        leases.request("robot-01", 1, start).unwrap();
+        notices(&mut rx[0]);
+        notices(&mut rx[1]);
+
+        assert!(!leases.expire("robot-01", start + TTL - Duration::from_secs(1)));
+        assert!(notices(&mut rx[0]).is_empty());
+
+        let expired = start + TTL;
+        assert_eq!(leases.check("robot-01", 1, expired), Err(LeaseError::NotHolder));
+        assert_eq!(notices(&mut rx[0]), [(None, "lease_expired")]);
+        assert_eq!(notices(&mut rx[1]), [(None, "lease_expired")]);
+
+        // 같은 만료는 다시 알리지 않는다.
+        assert!(!leases.expire("robot-01", expired));
+        assert_eq!(leases.check("robot-01", 1, expired), Err(LeaseError::NotHolder));
+        assert!(notices(&mut rx[0]).is_empty());
+
+        // 새로 부여된 lease가 만료되면 다시 알린다.
+        leases.request("robot-01", 2, expired).unwrap();
+        assert_eq!(notices(&mut rx[1]), [(Some(2), "granted")]);
+        assert!(leases.expire("robot-01", expired + TTL));
+        assert_eq!(notices(&mut rx[0]), [(Some(2), "granted"), (None, "lease_expired")]);
+        assert!(!leases.expire("robot-02", expired + TTL));
+    }
+
+    #[test]
+    fn holder_disconnect_releases_the_lease() {
+        let (mut leases, mut rx) = manager(&[1, 2, 3]);
+        let now = Instant::now();
+        leases.request("robot-01", 1, now).unwrap();
+        notices(&mut rx[1]);
+
+        // 보유자가 아닌 세션이 끊기면 lease는 그대로다.
+        leases.unregister("robot-01", 3);
+        assert_eq!(leases.holder("robot-01", now), Some(1));
+        assert!(notices(&mut rx[1]).is_empty());
+
+        leases.unregister("robot-01", 1);
+        assert_eq!(leases.holder("robot-01", now), None);
+        assert_eq!(notices(&mut rx[1]), [(None, "holder_disconnected")]);
+        assert_eq!(leases.handover("robot-01", 2, 1, now), Err(LeaseError::NotHolder));
+        leases.request("robot-01", 2, now).unwrap();
+        assert_eq!(leases.handover("robot-01", 2, 1, now), Err(LeaseError::UnknownSession(1)));
+    }
+
+    #[test]
+    fn lease_errors_map_to_control_error_codes() {
+        assert_eq!(LeaseError::HeldByOther { holder: 1 }.code(), ControlErrorCode::LeaseHeld);
+        assert_eq!(LeaseError::NotHolder.code(), ControlErrorCode::LeaseRequired);
+        assert_eq!(LeaseError::UnknownSession(1).code(), ControlErrorCode::InvalidPayload);
+    }
+}
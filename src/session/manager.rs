@@ -1,33 +1,268 @@
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use tokio::sync::RwLock;
 use std::sync::Arc;
-use tokio::sync::mpsc;
-use crate::protocol::robot::signaling::SignalMessage;
-pub type WsSender = mpsc::UnboundedSender<SignalMessage>;
+use tokio::sync::{mpsc, oneshot};
+use tokio::time::Duration;
+use crate::protocol::robot::signaling::{CommandResult, SignalMessage};
+use crate::config::configs::RateBucket;
+use crate::domain::control::ControlRequestType;
+use crate::session::lease::LeaseManager;
+use crate::session::rate_limit::RobotRateLimiter;
+pub type WsSender = mpsc::Sender<SignalMessage>;
+pub type WsReceiver = mpsc::Receiver<SignalMessage>;
+pub type CommandResultSender = oneshot::Sender<CommandResult>;
+
+/// 같은 robot에 붙은 여러 WS 연결을 구분하기 위한 세션 id
+pub type SessionId = u64;
+
+/// 시간 제한 move 뒤 예약된 STOP. 제거되어 `_cancel`이 drop 되면 대기 중인 timer task가 취소된다.
+struct MotionStop {
+    timer_id: u64,
+    // 예약한 control 세션 (세션 종료 시 정리용)
+    session_id: SessionId,
+    _cancel: oneshot::Sender<()>,
+}
 
 pub struct SessionManager {
-    sessions: HashMap<String, WsSender>,
+    // robot_id -> (session_id -> sender)
+    sessions: HashMap<String, HashMap<SessionId, WsSender>>,
+    next_session_id: SessionId,
+    // viewer마다 쌓아둘 수 있는 inbound SignalMessage 수 (넘치면 느린 viewer로 보고 끊는다)
+    viewer_queue_capacity: usize,
+
+    // control 세션의 독점 제어권
+    leases: LeaseManager,
+
+    // robot별 명령 전송 한도 (모든 control 세션 합산)
+    robot_rate: RobotRateLimiter,
+
+    // command_id -> robot의 CommandResult를 기다리는 control 세션
+    pending_commands: HashMap<String, CommandResultSender>,
+    // EMERGENCY_STOP이 나간 뒤 RESET_ESTOP 전까지 motion 명령을 막을 robot
+    estop_latched: HashSet<String>,
+
+    // robot_id -> 시간 제한 move 뒤 예약된 STOP
+    motion_stops: HashMap<String, MotionStop>,
+    next_timer_id: u64,
 }
 
 impl SessionManager {
-    pub fn new() -> Self {
+    pub fn new(lease_ttl: Duration, viewer_queue_capacity: usize, robot_rate: Option<RateBucket>) -> Self {
         Self {
             sessions: HashMap::new(),
+            next_session_id: 1,
+            viewer_queue_capacity: viewer_queue_capacity.max(1),
+            leases: LeaseManager::new(lease_ttl),
+            robot_rate: RobotRateLimiter::new(robot_rate),
+            pending_commands: HashMap::new(),
+            estop_latched: HashSet::new(),
+            motion_stops: HashMap::new(),
+            next_timer_id: 1,
         }
     }
 
-    pub fn get_ws_sender(&self, robot_id: &str) -> Option<WsSender> {
-        self.sessions.get(robot_id).cloned()
+    /// screen/control 세션 공통으로 쓰는 session_id 발급
+    pub fn next_session_id(&mut self) -> SessionId {
+        let session_id = self.next_session_id;
+        self.next_session_id += 1;
+        session_id
+    }
+
+    pub fn leases(&self) -> &LeaseManager {
+        &self.leases
+    }
+
+    pub fn leases_mut(&mut self) -> &mut LeaseManager {
+        &mut self.leases
+    }
+
+    pub fn robot_rate_mut(&mut self) -> &mut RobotRateLimiter {
+        &mut self.robot_rate
+    }
+
+    /// EMERGENCY_STOP 전송 후 호출. 이후 motion 명령은 reset 전까지 거절된다.
+    pub fn latch_estop(&mut self, robot_id: &str) {
+        self.estop_latched.insert(robot_id.to_string());
     }
 
-    pub fn insert(&mut self, robot_id: String, tx: WsSender) {
-        self.sessions.insert(robot_id, tx);
+    /// latch가 걸려 있었으면 해제하고 true
+    pub fn reset_estop(&mut self, robot_id: &str) -> bool {
+        self.estop_latched.remove(robot_id)
+    }
+
+    pub fn is_estop_latched(&self, robot_id: &str) -> bool {
+        self.estop_latched.contains(robot_id)
+    }
+
+    /// latch 중이라 거절해야 하는 명령인지 (motion 명령만 막고 stop/e_stop/reset_estop은 통과)
+    pub fn is_blocked_by_estop(&self, robot_id: &str, kind: ControlRequestType) -> bool {
+        kind.is_motion() && self.is_estop_latched(robot_id)
+    }
+
+    /// 시간 제한 move의 STOP을 예약한다. 이전 예약은 취소된다.
+    /// 돌려받은 receiver는 예약이 취소(교체)되면 깨어난다.
+    pub fn schedule_motion_stop(&mut self, robot_id: &str, session_id: SessionId) -> (u64, oneshot::Receiver<()>) {
+        let timer_id = self.next_timer_id;
+        self.next_timer_id += 1;
+
+        let (cancel, rx) = oneshot::channel();
+        self.motion_stops
+            .insert(robot_id.to_string(), MotionStop { timer_id, session_id, _cancel: cancel });
+        (timer_id, rx)
+    }
+
+    /// 새 motion/stop 명령이 나가면 예약된 STOP을 취소한다.
+    pub fn cancel_motion_stop(&mut self, robot_id: &str) -> bool {
+        self.motion_stops.remove(robot_id).is_some()
+    }
+
+    /// control 세션 종료 시 호출. 이 세션이 예약한 STOP이면 취소하고 true.
+    pub fn cancel_session_motion_stop(&mut self, robot_id: &str, session_id: SessionId) -> bool {
+        if self
+            .motion_stops
+            .get(robot_id)
+            .is_some_and(|stop| stop.session_id == session_id)
+        {
+            self.motion_stops.remove(robot_id);
+            return true;
+        }
+        false
+    }
+
+    /// timer 만료 시 호출. 아직 이 timer가 유효하면(취소/교체되지 않았으면) 제거하고 true.
+    pub fn take_motion_stop(&mut self, robot_id: &str, timer_id: u64) -> bool {
+        if self
+            .motion_stops
+            .get(robot_id)
+            .is_some_and(|stop| stop.timer_id == timer_id)
+        {
+            self.motion_stops.remove(robot_id);
+            return true;
+        }
+        false
     }
 
-    pub fn remove(&mut self, robot_id: &str) {
-        self.sessions.remove(robot_id);
+    /// robot-level ack을 요청한 명령을 등록한다. 결과는 `complete_command`로 전달된다.
+    pub fn register_command(&mut self, command_id: String, tx: CommandResultSender) {
+        self.pending_commands.insert(command_id, tx);
+    }
+
+    /// robot이 보낸 CommandResult를 명령을 보낸 control 세션으로 돌려준다.
+    /// 기다리는 세션이 없으면(gateway ack 명령, 이미 timeout) false.
+    pub fn complete_command(&mut self, result: CommandResult) -> bool {
+        match self.pending_commands.remove(&result.command_id) {
+            Some(tx) => tx.send(result).is_ok(),
+            None => false,
+        }
+    }
+
+    /// timeout 또는 전송 실패로 더 이상 결과를 기다리지 않는 명령 정리
+    pub fn cancel_command(&mut self, command_id: &str) {
+        self.pending_commands.remove(command_id);
+    }
+
+    /// robot에 연결된 모든 viewer의 sender (inbound SignalMessage fan-out 용)
+    pub fn get_ws_senders(&self, robot_id: &str) -> Vec<(SessionId, WsSender)> {
+        self.sessions
+            .get(robot_id)
+            .map(|viewers| viewers.iter().map(|(id, tx)| (*id, tx.clone())).collect())
+            .unwrap_or_default()
+    }
+
+    /// 새 viewer를 등록하고 발급된 session_id와 viewer 큐의 receiver를 돌려준다.
+    pub fn insert(&mut self, robot_id: String) -> (SessionId, WsReceiver) {
+        let session_id = self.next_session_id();
+        let (tx, rx) = mpsc::channel(self.viewer_queue_capacity);
+
+        self.sessions
+            .entry(robot_id)
+            .or_default()
+            .insert(session_id, tx);
+        (session_id, rx)
+    }
+
+    /// (robot_id, session_id, 대기 중인 메시지 수) 목록. 비어 있지 않은 viewer 큐만 돌려준다.
+    pub fn viewer_queue_depths(&self) -> Vec<(String, SessionId, usize)> {
+        self.sessions
+            .iter()
+            .flat_map(|(robot_id, viewers)| {
+                viewers.iter().filter_map(move |(id, tx)| {
+                    let depth = tx.max_capacity() - tx.capacity();
+                    (depth > 0).then(|| (robot_id.clone(), *id, depth))
+                })
+            })
+            .collect()
+    }
+
+    /// 끊어진 viewer 하나만 제거한다. 같은 robot의 다른 viewer는 그대로 유지된다.
+    pub fn remove(&mut self, robot_id: &str, session_id: SessionId) {
+        if let Some(viewers) = self.sessions.get_mut(robot_id) {
+            viewers.remove(&session_id);
+            if viewers.is_empty() {
+                self.sessions.remove(robot_id);
+            }
+        }
     }
 }
 
 
 pub type SharedSessions = Arc<RwLock<SessionManager>>;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn manager() -> SessionManager {
+        SessionManager::new(Duration::from_secs(30), 1, None)
+    }
+
+    #[test]
+    fn estop_latch_holds_until_reset() {
+        let mut sessions = manager();
+        assert!(!sessions.is_estop_latched("robot-01"));
+        assert!(!sessions.reset_estop("robot-01"));
+
+        sessions.latch_estop("robot-01");
+        sessions.latch_estop("robot-01");
+        assert!(sessions.is_estop_latched("robot-01"));
+        assert!(!sessions.is_estop_latched("robot-02"));
+
+        assert!(sessions.reset_estop("robot-01"));
+        assert!(!sessions.is_estop_latched("robot-01"));
+        // 이미 풀린 latch를 다시 reset해도 false
+        assert!(!sessions.reset_estop("robot-01"));
+    }
+
+    #[test]
+    fn latched_robot_rejects_motion_commands_only() {
+        let mut sessions = manager();
+        sessions.latch_estop("robot-01");
+
+        for kind in [
+            ControlRequestType::Move,
+            ControlRequestType::Velocity,
+            ControlRequestType::SetSpeed,
+            ControlRequestType::Dock,
+            ControlRequestType::NavigateTo,
+            ControlRequestType::Resume,
+            ControlRequestType::Custom,
+            ControlRequestType::Vendor,
+        ] {
+            assert!(sessions.is_blocked_by_estop("robot-01", kind), "{kind:?}");
+            assert!(!sessions.is_blocked_by_estop("robot-02", kind), "{kind:?}");
+        }
+        for kind in [
+            ControlRequestType::Stop,
+            ControlRequestType::EStop,
+            ControlRequestType::ResetEstop,
+            ControlRequestType::Pause,
+            ControlRequestType::Cancel,
+            ControlRequestType::Heartbeat,
+        ] {
+            assert!(!sessions.is_blocked_by_estop("robot-01", kind), "{kind:?}");
+        }
+
+        sessions.reset_estop("robot-01");
+        assert!(!sessions.is_blocked_by_estop("robot-01", ControlRequestType::Move));
+    }
+}
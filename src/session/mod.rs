@@ -1 +1,3 @@
-pub mod manager;
\ No newline at end of file
+pub mod manager;
+pub mod lease;
+pub mod rate_limit;
\ No newline at end of file
@@ -0,0 +1,313 @@
+use std::collections::HashMap;
+use std::fmt;
+use tokio::time::Instant;
+
+use crate::config::configs::{RateBucket, RateLimitConfig};
+use crate::domain::control::ControlRequestType;
+
+/// 어떤 한도에 걸렸는지 (control_error 메시지용)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RateLimitScope {
+    Command(ControlRequestType),
+    Session,
+    Robot,
+}
+
+impl fmt::Display for RateLimitScope {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RateLimitScope::Command(kind) => write!(f, "rate limit exceeded for {kind:?} commands"),
+            RateLimitScope::Session => write!(f, "rate limit exceeded for this session"),
+            RateLimitScope::Robot => write!(f, "rate limit exceeded for this robot"),
+        }
+    }
+}
+
+/// 초당 `rate`개씩 채워지고 최대 `burst`개까지 쌓이는 token bucket
+#[derive(Debug, Clone)]
+pub struct TokenBucket {
+    rate: f64,
+    burst: f64,
+    tokens: f64,
+    updated: Instant,
+}
+
+impl TokenBucket {
+    pub fn new(config: &RateBucket, now: Instant) -> Self {
+        let burst = config.burst.max(1.0);
+        Self {
+            rate: config.rate.max(0.0),
+            burst,
+            tokens: burst,
+            updated: now,
+        }
+    }
+
+    fn refill(&mut self, now: Instant) {
+        let elapsed = now.saturating_duration_since(self.updated).as_secs_f64();
+        self.tokens = (self.tokens + elapsed * self.rate).min(self.burst);
+        self.updated = now;
+    }
+
+    /// 토큰을 소비하지 않고 통과 가능한지만 확인한다.
+    pub fn has_token(&mut self, now: Instant) -> bool {
+        self.refill(now);
+        self.tokens >= 1.0
+    }
+
+    /// 지금 채워 보면 가득 찬 상태인지 (새로 만든 bucket과 같으므로 버려도 된다)
+    fn is_full(&self, now: Instant) -> bool {
+        let elapsed = now.saturating_duration_since(self.updated).as_secs_f64();
+        self.tokens + elapsed * self.rate >= self.burst
+    }
+
+    /// `has_token`으로 확인한 뒤 호출한다.
+    pub fn take(&mut self) {
+        self.tokens = (self.tokens - 1.0).max(0.0);
+    }
+
+    pub fn try_take(&mut self, now: Instant) -> bool {
+        if !self.has_token(now) {
+            return false;
+        }
+        self.take();
+        true
+    }
+}
+
+/// control 세션 하나의 한도 (세션 전체 + 명령 종류별). 세션 task가 직접 들고 있는다.
+pub struct SessionRateLimiter {
+    session: Option<TokenBucket>,
+    commands: HashMap<ControlRequestType, TokenBucket>,
+}
+
+impl SessionRateLimiter {
+    pub fn new(config: &RateLimitConfig, now: Instant) -> Self {
+        Self {
+            session: config.session.as_ref().map(|b| TokenBucket::new(b, now)),
+            commands: config
+                .commands
+                .iter()
+                .map(|(kind, b)| (*kind, TokenBucket::new(b, now)))
+                .collect(),
+        }
+    }
+
+    /// 토큰을 소비하지 않고 확인만 한다. robot 한도까지 통과하면 `consume`을 호출한다.
+    pub fn check(&mut self, kind: ControlRequestType, now: Instant) -> Result<(), RateLimitScope> {
+        if let Some(bucket) = self.commands.get_mut(&kind)
+            && !bucket.has_token(now)
+        {
+            return Err(RateLimitScope::Command(kind));
+        }
+        if let Some(bucket) = self.session.as_mut()
+            && !bucket.has_token(now)
+        {
+            return Err(RateLimitScope::Session);
+        }
+        Ok(())
+    }
+
+    pub fn consume(&mut self, kind: ControlRequestType) {
+        if let Some(bucket) = self.commands.get_mut(&kind) {
+            bucket.take();
+        }
+        if let Some(bucket) = self.session.as_mut() {
+            bucket.take();
+        }
+    }
+}
+
+/// robot별 한도. 같은 robot에 붙은 모든 control 세션이 함께 쓴다.
+/// robot_id는 클라이언트가 정하므로, 가득 찬(한동안 쓰지 않은) bucket은 정리해 map이 계속 커지지 않게 한다.
+pub struct RobotRateLimiter {
+    config: Option<RateBucket>,
+    buckets: HashMap<String, TokenBucket>,
+}
+
+impl RobotRateLimiter {
+    pub fn new(config: Option<RateBucket>) -> Self {
+        Self {
+            config,
+            buckets: HashMap::new(),
+        }
+    }
+
+    pub fn try_take(&mut self, robot_id: &str, now: Instant) -> bool {
+        let Some(config) = &self.config else {
+            return true;
+        };
+        self.buckets.retain(|id, bucket| id == robot_id || !bucket.is_full(now));
+        self.buckets
+            .entry(robot_id.to_string())
+            .or_insert_with(|| TokenBucket::new(config, now))
+            .try_take(now)
+    }
+}
+
+/// 세션/명령 종류별 한도를 먼저 확인하고, robot 한도까지 통과했을 때만 토큰을 소비한다.
+/// stop/e_stop은 어떤 한도에도 걸리지 않고 토큰도 쓰지 않는다.
+pub fn take_command(
+    session: &mut SessionRateLimiter,
+    robot: &mut RobotRateLimiter,
+    robot_id: &str,
+    kind: ControlRequestType,
+    now: Instant,
+) -> Result<(), RateLimitScope> {
+    if kind.is_rate_limit_exempt() {
+        return Ok(());
+    }
+    session.check(kind, now)?;
+    if !robot.try_take(robot_id, now) {
+        return Err(RateLimitScope::Robot);
+    }
+    session.consume(kind);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tokio::time::Duration;
+
+    fn bucket(rate: f64, burst: f64) -> RateBucket {
+        RateBucket { rate, burst }
+    }
+
+    fn after(start: Instant, millis: u64) -> Instant {
+        start + Duration::from_millis(millis)
+    }
+
+    #[test]
+    fn bucket_allows_a_burst_then_refills_at_rate() {
+        let start = Instant::now();
+        let mut bucket = TokenBucket::new(&bucket(2.0, 3.0), start);
+        assert!((0..3).all(|_| bucket.try_take(start)));
+        assert!(!bucket.try_take(start));
+
+        // 초당 2개: 400ms 뒤에는 아직 1개가 안 되고, 500ms 뒤에 1개
+        assert!(!bucket.try_take(after(start, 400)));
+        assert!(bucket.try_take(after(start, 500)));
+        assert!(!bucket.try_take(after(start, 500)));
+
+        // 오래 쉬어도 burst 이상은 쌓이지 않는다.
+        let later = after(start, 60_000);
+        assert!((0..3).all(|_| bucket.try_take(later)));
+        assert!(!bucket.try_take(later));
+    }
+
+    #[test]
+    fn session_limit_counts_every_command_type() {
+        let config = RateLimitConfig {
+            session: Some(bucket(1.0, 2.0)),
+            ..RateLimitConfig::default()
+        };
+        let now = Instant::now();
+        let mut session = SessionRateLimiter::new(&config, now);
+        let mut robot = RobotRateLimiter::new(None);
+
+        assert!(take_command(&mut session, &mut robot, "robot-01", ControlRequestType::Move, now).is_ok());
+        assert!(take_command(&mut session, &mut robot, "robot-01", ControlRequestType::Dock, now).is_ok());
+        assert_eq!(
+            take_command(&mut session, &mut robot, "robot-01", ControlRequestType::Velocity, now),
+            Err(RateLimitScope::Session)
+        );
+        assert!(take_command(&mut session, &mut robot, "robot-01", ControlRequestType::Velocity, after(now, 1_000)).is_ok());
+    }
+
+    #[test]
+    fn command_limit_applies_to_its_type_only() {
+        let config = RateLimitConfig {
+            commands: HashMap::from([(ControlRequestType::Dock, bucket(0.5, 1.0))]),
+            ..RateLimitConfig::default()
+        };
+        let now = Instant::now();
+        let mut session = SessionRateLimiter::new(&config, now);
+        let mut robot = RobotRateLimiter::new(None);
+
+        assert!(take_command(&mut session, &mut robot, "robot-01", ControlRequestType::Dock, now).is_ok());
+        assert_eq!(
+            take_command(&mut session, &mut robot, "robot-01", ControlRequestType::Dock, now),
+            Err(RateLimitScope::Command(ControlRequestType::Dock))
+        );
+        assert!((0..10).all(|_| take_command(&mut session, &mut robot, "robot-01", ControlRequestType::Move, now).is_ok()));
+        assert!(take_command(&mut session, &mut robot, "robot-01", ControlRequestType::Dock, after(now, 2_000)).is_ok());
+    }
+
+    #[test]
+    fn robot_limit_is_shared_by_sessions_and_separate_per_robot() {
+        let now = Instant::now();
+        let mut robot = RobotRateLimiter::new(Some(bucket(1.0, 2.0)));
+        let mut first = SessionRateLimiter::new(&RateLimitConfig::default(), now);
+        let mut second = SessionRateLimiter::new(&RateLimitConfig::default(), now);
+
+        assert!(take_command(&mut first, &mut robot, "robot-01", ControlRequestType::Move, now).is_ok());
+        assert!(take_command(&mut second, &mut robot, "robot-01", ControlRequestType::Move, now).is_ok());
+        assert_eq!(
+            take_command(&mut first, &mut robot, "robot-01", ControlRequestType::Move, now),
+            Err(RateLimitScope::Robot)
+        );
+        assert!(take_command(&mut first, &mut robot, "robot-02", ControlRequestType::Move, now).is_ok());
+    }
+
+    #[test]
+    fn robot_limit_rejection_does_not_consume_session_tokens() {
+        let config = RateLimitConfig {
+            session: Some(bucket(0.0, 1.0)),
+            ..RateLimitConfig::default()
+        };
+        let now = Instant::now();
+        let mut session = SessionRateLimiter::new(&config, now);
+        let mut robot = RobotRateLimiter::new(Some(bucket(0.0, 1.0)));
+        assert!(robot.try_take("robot-01", now));
+
+        assert_eq!(
+            take_command(&mut session, &mut robot, "robot-01", ControlRequestType::Move, now),
+            Err(RateLimitScope::Robot)
+        );
+        assert!(take_command(&mut session, &mut robot, "robot-02", ControlRequestType::Move, now).is_ok());
+    }
+
+    #[test]
+    fn stop_and_e_stop_are_exempt() {
+        let config = RateLimitConfig {
+            session: Some(bucket(0.0, 1.0)),
+            robot: None,
+            commands: HashMap::from([(ControlRequestType::Stop, bucket(0.0, 1.0))]),
+        };
+        let now = Instant::now();
+        let mut session = SessionRateLimiter::new(&config, now);
+        let mut robot = RobotRateLimiter::new(Some(bucket(0.0, 1.0)));
+        assert!(take_command(&mut session, &mut robot, "robot-01", ControlRequestType::Move, now).is_ok());
+
+        // 세션/robot/명령 종류 한도가 모두 소진돼도 정지 명령은 통과한다.
+        for kind in [ControlRequestType::Stop, ControlRequestType::EStop] {
+            for _ in 0..5 {
+                assert!(take_command(&mut session, &mut robot, "robot-01", kind, now).is_ok());
+            }
+        }
+        assert_eq!(
+            take_command(&mut session, &mut robot, "robot-01", ControlRequestType::Move, now),
+            Err(RateLimitScope::Session)
+        );
+    }
+
+    #[test]
+    fn idle_robot_buckets_are_evicted() {
+        let start = Instant::now();
+        let mut robot = RobotRateLimiter::new(Some(bucket(10.0, 5.0)));
+        for i in 0..100 {
+            assert!(robot.try_take(&format!("robot-{i}"), start));
+        }
+        assert_eq!(robot.buckets.len(), 100);
+
+        // 100ms 뒤에는 모두 다시 가득 차므로 지금 쓰는 robot만 남는다.
+        assert!(robot.try_take("robot-0", after(start, 100)));
+        assert_eq!(robot.buckets.len(), 1);
+        assert!(robot.buckets.contains_key("robot-0"));
+
+        // 아직 채워지는 중인 bucket은 남는다 (버리면 한도가 초기화된다)
+        assert!(robot.try_take("robot-1", after(start, 100)));
+        assert_eq!(robot.buckets.len(), 2);
+    }
+}